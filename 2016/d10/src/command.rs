@@ -0,0 +1,38 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Give {
+    Output(usize),
+    Bot(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    // a chip of the given value starts in a bot
+    Input(usize, usize),
+    // a bot and where its low and high chips go
+    Bot(usize, Give, Give),
+}
+
+peg::parser! {
+    grammar commands() for str {
+        pub rule parse() -> Vec<Command>
+            = newline()* c:(command() ** (newline()+)) newline()* { c }
+
+        rule command() -> Command
+            = "value " v:number() " goes to bot " b:number() { Command::Input(v, b) }
+            / "bot " b:number() " gives low to " low:give() " and high to " high:give() { Command::Bot(b, low, high) }
+
+        rule give() -> Give
+            = "bot " n:number() { Give::Bot(n) }
+            / "output " n:number() { Give::Output(n) }
+
+        rule number() -> usize
+            = n:$(['0'..='9']+) {? n.parse().or(Err("number")) }
+
+        rule newline()
+            = "\r"? "\n"
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Command>, String> {
+    commands::parse(input).map_err(|e| e.to_string())
+}
@@ -0,0 +1,243 @@
+extern crate peg;
+extern crate petgraph;
+
+mod command;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use petgraph::Direction;
+use petgraph::algo::toposort;
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+pub use command::{parse, Command, Give};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FactoryError {
+    Parse(String),
+    // a bot with two rules for where its chips go
+    DuplicateRule(usize),
+    // a bot holding two chips with nowhere to put them
+    MissingRule(usize),
+    // a bot that would be handed a third chip
+    TooManyChips(usize),
+    // a bot that feeds, however indirectly, back into itself
+    Cycle(usize),
+}
+
+impl fmt::Display for FactoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FactoryError::Parse(ref msg) => write!(f, "couldn't parse instructions: {}", msg),
+            FactoryError::DuplicateRule(bot) => write!(f, "bot {} has more than one rule", bot),
+            FactoryError::MissingRule(bot) => write!(f, "bot {} holds two chips but has no rule", bot),
+            FactoryError::TooManyChips(bot) => write!(f, "bot {} would hold more than two chips", bot),
+            FactoryError::Cycle(bot) => write!(f, "bot {} passes chips back to itself", bot),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Node {
+    Input(usize),
+    Bot(usize),
+    Output(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edge {
+    Input,
+    Low,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comparison {
+    pub bot: usize,
+    pub low: usize,
+    pub high: usize,
+}
+
+// the wiring of the factory: every chip flows along the edges from an input, through bots,
+// to an output, so the graph must be acyclic and a topological order is an order bots can act in
+#[derive(Debug)]
+pub struct Factory {
+    graph: Graph<Node, Edge>,
+    nodes: HashMap<Node, NodeIndex>,
+}
+
+impl Factory {
+    pub fn new(commands: &[Command]) -> Result<Factory, FactoryError> {
+        let mut factory = Factory { graph: Graph::new(), nodes: HashMap::new() };
+        let mut ruled = HashSet::new();
+
+        for &command in commands {
+            match command {
+                Command::Input(value, bot) => {
+                    let from = factory.graph.add_node(Node::Input(value));
+                    let to = factory.node(Node::Bot(bot));
+                    factory.graph.add_edge(from, to, Edge::Input);
+                }
+                Command::Bot(bot, low, high) => {
+                    if !ruled.insert(bot) {
+                        return Err(FactoryError::DuplicateRule(bot));
+                    }
+                    let from = factory.node(Node::Bot(bot));
+                    for &(give, edge) in [(low, Edge::Low), (high, Edge::High)].iter() {
+                        let to = factory.node(match give {
+                            Give::Bot(n) => Node::Bot(n),
+                            Give::Output(n) => Node::Output(n),
+                        });
+                        factory.graph.add_edge(from, to, edge);
+                    }
+                }
+            }
+        }
+
+        Ok(factory)
+    }
+
+    fn node(&mut self, node: Node) -> NodeIndex {
+        let graph = &mut self.graph;
+        *self.nodes.entry(node).or_insert_with(|| graph.add_node(node))
+    }
+
+    fn target(&self, from: NodeIndex, edge: Edge) -> Option<NodeIndex> {
+        self.graph.edges_directed(from, Direction::Outgoing)
+            .find(|e| *e.weight() == edge)
+            .map(|e| e.target())
+    }
+
+    // hands every chip along, visiting each bot once after everything that feeds it
+    pub fn run(&self) -> Result<Log, FactoryError> {
+        let order = toposort(&self.graph, None).map_err(|cycle| match self.graph[cycle.node_id()] {
+            Node::Bot(bot) => FactoryError::Cycle(bot),
+            // only bots have outgoing edges that can lead back to themselves
+            _ => unreachable!(),
+        })?;
+
+        let mut held: HashMap<NodeIndex, Vec<usize>> = HashMap::new();
+        let mut log = Log { comparisons: vec![], outputs: BTreeMap::new() };
+
+        for idx in order {
+            match self.graph[idx] {
+                Node::Input(value) => {
+                    // unwrap: every input is wired to the bot it starts in
+                    let to = self.target(idx, Edge::Input).unwrap();
+                    held.entry(to).or_insert_with(Vec::new).push(value);
+                }
+                Node::Bot(bot) => {
+                    let mut chips = held.remove(&idx).unwrap_or_default();
+                    if chips.len() > 2 {
+                        return Err(FactoryError::TooManyChips(bot));
+                    }
+                    if chips.len() < 2 {
+                        continue;
+                    }
+                    chips.sort();
+
+                    let (low, high) = match (self.target(idx, Edge::Low), self.target(idx, Edge::High)) {
+                        (Some(low), Some(high)) => (low, high),
+                        _ => return Err(FactoryError::MissingRule(bot)),
+                    };
+                    log.comparisons.push(Comparison { bot, low: chips[0], high: chips[1] });
+                    held.entry(low).or_insert_with(Vec::new).push(chips[0]);
+                    held.entry(high).or_insert_with(Vec::new).push(chips[1]);
+                }
+                Node::Output(bin) => {
+                    let chips = held.remove(&idx).unwrap_or_default();
+                    log.outputs.insert(bin, chips);
+                }
+            }
+        }
+
+        Ok(log)
+    }
+}
+
+impl ::std::str::FromStr for Factory {
+    type Err = FactoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Factory::new(&parse(s).map_err(FactoryError::Parse)?)
+    }
+}
+
+// everything that happened on a run of the factory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Log {
+    comparisons: Vec<Comparison>,
+    outputs: BTreeMap<usize, Vec<usize>>,
+}
+
+impl Log {
+    // every comparison in the order the bots made them
+    pub fn comparisons(&self) -> &[Comparison] {
+        &self.comparisons
+    }
+
+    pub fn who_compared(&self, a: usize, b: usize) -> Option<usize> {
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        self.comparisons.iter().find(|c| c.low == low && c.high == high).map(|c| c.bot)
+    }
+
+    // the chips that ended up in an output bin, in the order they arrived
+    pub fn output(&self, bin: usize) -> &[usize] {
+        self.outputs.get(&bin).map_or(&[], |chips| chips.as_slice())
+    }
+
+    // the product of the first chip in each of the bins, if they all got one
+    pub fn output_product(&self, bins: RangeInclusive<usize>) -> Option<usize> {
+        bins.map(|bin| self.output(bin).first().cloned()).product()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SAMPLE: &str = "value 5 goes to bot 2
+bot 2 gives low to bot 1 and high to bot 0
+value 3 goes to bot 1
+bot 1 gives low to output 1 and high to bot 0
+bot 0 gives low to output 2 and high to output 0
+value 2 goes to bot 2
+";
+
+    fn run(input: &str) -> Result<Log, FactoryError> {
+        input.parse::<Factory>()?.run()
+    }
+
+    #[test]
+    fn sample() {
+        let log = run(SAMPLE).unwrap();
+        assert_eq!(log.who_compared(5, 2), Some(2));
+        assert_eq!(log.who_compared(2, 3), Some(1));
+        assert_eq!(log.who_compared(2, 4), None);
+        assert_eq!(log.output(0), &[5]);
+        assert_eq!(log.output(1), &[2]);
+        assert_eq!(log.output(2), &[3]);
+        assert_eq!(log.output_product(0..=2), Some(30));
+        assert_eq!(log.output_product(0..=3), None);
+        assert_eq!(log.comparisons(), &[
+            Comparison { bot: 2, low: 2, high: 5 },
+            Comparison { bot: 1, low: 2, high: 3 },
+            Comparison { bot: 0, low: 3, high: 5 },
+        ]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(run("bot 1 gives low to bot 2 and high to bot 2\nbot 1 gives low to output 0 and high to output 1\n"),
+                   Err(FactoryError::DuplicateRule(1)));
+        assert_eq!(run("value 1 goes to bot 0\nvalue 2 goes to bot 0\n").unwrap_err(),
+                   FactoryError::MissingRule(0));
+        assert_eq!(run("value 1 goes to bot 0\nvalue 2 goes to bot 0\nvalue 3 goes to bot 0\n").unwrap_err(),
+                   FactoryError::TooManyChips(0));
+        assert!(matches!(run("bot 0 gives low to bot 1 and high to output 0\nbot 1 gives low to bot 0 and high to output 1\n"),
+                         Err(FactoryError::Cycle(_))));
+        assert!(run("value x goes to bot 0\n").is_err());
+    }
+}
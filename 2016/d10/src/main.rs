@@ -0,0 +1,35 @@
+extern crate d10;
+
+use d10::Factory;
+
+fn load_input() -> String {
+    use std::fs::File;
+    use std::io::prelude::*;
+    use std::path::Path;
+
+    let path = Path::new("input.txt");
+
+    let mut file = match File::open(path) {
+        Err(why) => panic!("couldn't open: {}", why),
+        Ok(file) => file,
+    };
+
+    let mut s = String::new();
+    match file.read_to_string(&mut s) {
+        Err(why) => panic!("couldn't read: {}", why),
+        Ok(_) => s,
+    }
+}
+
+fn main() -> Result<(), String> {
+    let input = load_input();
+    let factory: Factory = input.parse().map_err(|e: d10::FactoryError| e.to_string())?;
+    let log = factory.run().map_err(|e| e.to_string())?;
+
+    let bot = log.who_compared(17, 61).ok_or("no bot ever compared chips 17 and 61")?;
+    println!("part one: {}", bot);
+
+    let product = log.output_product(0..=2).ok_or("outputs 0, 1 and 2 didn't all receive a chip")?;
+    println!("part two: {}", product);
+    Ok(())
+}
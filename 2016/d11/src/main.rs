@@ -1,5 +1,7 @@
 extern crate itertools;
+extern crate render;
 use itertools::Itertools;
+use render::{Canvas, Render, Rgb};
 use std::hash::{Hash,Hasher};
 
 const LOG : bool = false;
@@ -113,14 +115,37 @@ fn space(amt: usize) -> String {
     std::iter::repeat(" ").take(amt).collect()
 }
 
+struct Snapshot<'a> {
+    building: &'a Vec<Vec<Item>>,
+    elevator: usize,
+}
+
+impl<'a> Render for Snapshot<'a> {
+    fn render(&self, canvas: &mut Canvas) {
+        use Item::*;
+        for (row, (number, floor)) in self.building.iter().enumerate().rev().enumerate() {
+            let mut x = canvas.write(0, row, &format!("F{}", number + 1));
+            if number == self.elevator {
+                x = canvas.write_colored(x, row, "*", Rgb(255, 255, 0));
+            }
+            x = canvas.write(x.max(3), row, ": ");
+            for item in floor {
+                let color = match *item {
+                    Generator(_) => Rgb(255, 96, 96),
+                    Microchip(_) => Rgb(96, 160, 255),
+                    Elevator => Rgb(255, 255, 0),
+                };
+                x = canvas.write_colored(x, row, &format!("{:?} ", item), color);
+            }
+        }
+    }
+}
+
 fn print_building(building: &Vec<Vec<Item>>, elevator: usize, steps: usize) {
     println!("{}STEP {}", space(steps * 2), steps + 1);
-    for (number, floor) in building.iter().enumerate().rev() {
-        print!("{}F{}{}: ", space(steps * 2), number+1, if number == elevator { "*" } else { " " } );
-        for item in floor {
-            print!("{:?} ", item);
-        }
-        println!("");
+    let snapshot = Snapshot { building, elevator };
+    for line in snapshot.ascii(true).lines() {
+        println!("{}{}", space(steps * 2), line);
     }
 }
 
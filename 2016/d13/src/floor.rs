@@ -0,0 +1,212 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use render::{Canvas, Render};
+
+pub type Pos = (usize, usize);
+
+pub const START: Pos = (1, 1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Floor {
+    secret: usize,
+}
+
+impl Floor {
+    pub fn new(secret: usize) -> Self {
+        Floor { secret }
+    }
+
+    pub fn is_open(&self, (x, y): Pos) -> bool {
+        let val = x * x + 3 * x + 2 * x * y + y + y * y + self.secret;
+        val.count_ones().is_multiple_of(2)
+    }
+
+    fn neighbours(&self, (x, y): Pos) -> impl Iterator<Item = Pos> + '_ {
+        let steps = [
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+            x.checked_sub(1).map(|x| (x, y)),
+            y.checked_sub(1).map(|y| (x, y)),
+        ];
+        IntoIterator::into_iter(steps).flatten().filter(move |&p| self.is_open(p))
+    }
+
+    // breadth-first from `start` over everything within `max_steps`, stopping early once `goal`
+    // has been reached
+    pub fn distance_field(&self, start: Pos, goal: Option<Pos>, max_steps: usize) -> DistanceField {
+        let mut field = DistanceField { start, dist: HashMap::new(), parent: HashMap::new() };
+        if !self.is_open(start) {
+            return field;
+        }
+
+        let mut queue = VecDeque::new();
+        field.dist.insert(start, 0);
+        queue.push_back(start);
+
+        while let Some(pos) = queue.pop_front() {
+            let steps = field.dist[&pos];
+            if Some(pos) == goal || steps == max_steps {
+                // everything left in the queue is at least as far away
+                break;
+            }
+
+            for next in self.neighbours(pos) {
+                if let Entry::Vacant(slot) = field.dist.entry(next) {
+                    slot.insert(steps + 1);
+                    field.parent.insert(next, pos);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        field
+    }
+
+    // the floor goes on forever, so a goal walled off from an open-ended region around `start`
+    // would never be found; the search gives up after `max_steps`
+    pub fn distance(&self, start: Pos, goal: Pos, max_steps: usize) -> Option<usize> {
+        if !self.is_open(goal) {
+            return None;
+        }
+        self.distance_field(start, Some(goal), max_steps).distance(goal)
+    }
+
+    pub fn reachable_within(&self, start: Pos, k: usize) -> usize {
+        self.distance_field(start, None, k).len()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DistanceField {
+    start: Pos,
+    dist: HashMap<Pos, usize>,
+    // the cell each explored cell was first reached from
+    parent: HashMap<Pos, Pos>,
+}
+
+impl DistanceField {
+    pub fn distance(&self, pos: Pos) -> Option<usize> {
+        self.dist.get(&pos).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.dist.len()
+    }
+
+    // one of the shortest paths from the start to `pos`, both ends included
+    pub fn path_to(&self, pos: Pos) -> Option<Vec<Pos>> {
+        self.dist.get(&pos)?;
+
+        let mut path = vec![pos];
+        let mut at = pos;
+        while let Some(&prev) = self.parent.get(&at) {
+            path.push(prev);
+            at = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// the explored part of a floor, with an optional path drawn over it
+pub struct Explored<'a> {
+    pub floor: &'a Floor,
+    pub field: &'a DistanceField,
+    pub path: &'a [Pos],
+}
+
+impl<'a> Render for Explored<'a> {
+    // walls are '#', explored cells 'o', unexplored open cells '.' and the path 'O'
+    fn render(&self, canvas: &mut Canvas) {
+        let width = self.field.dist.keys().map(|&(x, _)| x + 2).max().unwrap_or(0);
+        let height = self.field.dist.keys().map(|&(_, y)| y + 2).max().unwrap_or(0);
+
+        for y in 0..height {
+            for x in 0..width {
+                let glyph = if !self.floor.is_open((x, y)) {
+                    '#'
+                } else if self.field.dist.contains_key(&(x, y)) {
+                    'o'
+                } else {
+                    '.'
+                };
+                canvas.put(x, y, glyph);
+            }
+        }
+
+        for &(x, y) in self.path {
+            canvas.put(x, y, 'O');
+        }
+        canvas.put(self.field.start.0, self.field.start.1, 'S');
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn within(field: &DistanceField, k: usize) -> usize {
+        field.dist.values().filter(|&&d| d <= k).count()
+    }
+
+    #[test]
+    fn walls() {
+        let floor = Floor::new(10);
+        let row: String = (0..10).map(|x| if floor.is_open((x, 0)) { '.' } else { '#' }).collect();
+        assert_eq!(row, ".#.####.##");
+    }
+
+    #[test]
+    fn sample() {
+        let floor = Floor::new(10);
+        assert_eq!(floor.distance(START, (7, 4), 100), Some(11));
+        assert_eq!(floor.distance(START, (7, 4), 10), None);
+        assert_eq!(floor.distance(START, (1, 0), 100), None);
+
+        let field = floor.distance_field(START, None, 5);
+        assert_eq!(within(&field, 2), 5);
+        assert_eq!(within(&field, 3), 6);
+        assert_eq!(within(&field, 4), 9);
+        assert_eq!(field.len(), 11);
+        assert_eq!(floor.reachable_within(START, 5), 11);
+    }
+
+    #[test]
+    fn walled_off_goal() {
+        // (5, 3) is open but every neighbour is a wall
+        let floor = Floor::new(10);
+        assert!(floor.is_open((5, 3)));
+        assert!(!floor.neighbours((5, 3)).any(|_| true));
+        assert_eq!(floor.distance(START, (5, 3), 500), None);
+        assert_eq!(floor.distance((5, 3), START, usize::MAX), None);
+    }
+
+    #[test]
+    fn path() {
+        let floor = Floor::new(10);
+        let field = floor.distance_field(START, Some((7, 4)), usize::MAX);
+        let path = field.path_to((7, 4)).unwrap();
+
+        assert_eq!(path.len(), 12);
+        assert_eq!(path.first(), Some(&START));
+        assert!(path.windows(2).all(|w| {
+            let ((ax, ay), (bx, by)) = (w[0], w[1]);
+            ax.max(bx) - ax.min(bx) + ay.max(by) - ay.min(by) == 1 && floor.is_open(w[1])
+        }));
+    }
+
+    #[test]
+    fn render() {
+        let floor = Floor::new(10);
+        let field = floor.distance_field(START, None, 2);
+        let path = field.path_to((1, 2)).unwrap();
+        let view = Explored { floor: &floor, field: &field, path: &path };
+        assert_eq!(view.text(), "\
+o#.#
+oS#.
+#Oo.
+###.
+");
+    }
+}
@@ -1,10 +1,12 @@
+extern crate numeric;
 extern crate regex;
 
+use numeric::{Congruence, CrtError};
 use regex::Regex;
 
 const SAMPLE : &'static str = "Disc #1 has 5 positions; at time=0, it is at position 4.
 Disc #2 has 2 positions; at time=0, it is at position 1.";
-const ANSWER : usize = 5;
+const ANSWER : u64 = 5;
 
 const REAL1 : &'static str = "Disc #1 has 17 positions; at time=0, it is at position 1.
 Disc #2 has 7 positions; at time=0, it is at position 0.
@@ -21,27 +23,96 @@ Disc #5 has 3 positions; at time=0, it is at position 0.
 Disc #6 has 13 positions; at time=0, it is at position 5.
 Disc #7 has 11 positions; at time=0, it is at position 0.";
 
-fn parse_input(input: &str) -> Vec<(usize, usize)> {
-    let re = Regex::new(r"Disc #(\d) has (\d+) positions; at time=0, it is at position (\d+)").unwrap();
-    input.lines().map(|line| match re.captures(line) {
-        Some(cap) => {
-            (cap.at(2).unwrap().parse().unwrap(), cap.at(3).unwrap().parse().unwrap())
-        },
-        None => panic!("line didn't match: {}", line),
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Disc {
+    number: usize,
+    positions: usize,
+    start: usize,
+}
+
+impl Disc {
+    // dropping at `time`, the capsule reaches this disc `number` seconds later, and needs
+    // start + time + number = 0 mod positions
+    fn congruence(&self) -> Congruence {
+        Congruence::new(-((self.start + self.number) as i128), self.positions as u64)
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<Disc>, String> {
+    let re = Regex::new(r"Disc #(\d+) has (\d+) positions; at time=0, it is at position (\d+)")
+        .map_err(|e| e.to_string())?;
+    let number = |n: &str| n.parse::<usize>().map_err(|e| format!("{}: {}", n, e));
+
+    input.lines().map(|line| {
+        let cap = re.captures(line).ok_or_else(|| format!("line didn't match: {}", line))?;
+        let disc = Disc { number: number(&cap[1])?, positions: number(&cap[2])?, start: number(&cap[3])? };
+        if disc.positions == 0 {
+            return Err(format!("disc #{} has no positions", disc.number));
+        }
+        Ok(disc)
     }).collect()
 }
 
-fn part_one(input: Vec<(usize, usize)>) -> usize {
-    for time in 0.. {
-        if input.iter().zip(time+1..).all(|(&(positions, start), time)| ((start + time) % positions) == 0) {
-            return time;
+fn part_one(input: Vec<Disc>) -> Result<u64, CrtError> {
+    numeric::crt(input.iter().map(Disc::congruence)).map(|c| c.residue)
+}
+
+fn main() -> Result<(), String> {
+    assert_eq!(part_one(parse_input(SAMPLE)?), Ok(ANSWER));
+
+    let one = part_one(parse_input(REAL1)?).map_err(|e| format!("part one: {}", e))?;
+    println!("part one: {}", one);
+    let two = part_one(parse_input(REAL2)?).map_err(|e| format!("part two: {}", e))?;
+    println!("part two: {}", two);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // the first drop time that lines every disc up, by trying each time within one full period
+    fn brute_force(discs: &[Disc]) -> Option<u64> {
+        let period = discs.iter().fold(1, |acc, d| numeric::lcm(acc, d.positions as u64));
+        (0..period).find(|&t| discs.iter().all(|d| (d.start as u64 + t + d.number as u64).is_multiple_of(d.positions as u64)))
+    }
+
+    fn disc(number: usize, positions: usize, start: usize) -> Disc {
+        Disc { number, positions, start }
+    }
+
+    #[test]
+    fn sample() {
+        let discs = parse_input(SAMPLE).unwrap();
+        assert_eq!(discs[0], disc(1, 5, 4));
+        assert_eq!(part_one(discs.clone()), Ok(ANSWER));
+        assert_eq!(brute_force(&discs), Some(ANSWER));
+    }
+
+    #[test]
+    fn shared_factors() {
+        let discs = vec![disc(1, 4, 1), disc(2, 6, 2), disc(3, 9, 4)];
+        let expected = brute_force(&discs);
+        assert!(expected.is_some());
+        assert_eq!(part_one(discs).ok(), expected);
+
+        for discs in [parse_input(REAL1).unwrap(), vec![disc(1, 6, 5), disc(2, 10, 0), disc(3, 15, 3)]] {
+            assert_eq!(part_one(discs.clone()).ok(), brute_force(&discs));
         }
     }
-    unreachable!();
-}
 
-fn main() {
-    assert_eq!(part_one(parse_input(SAMPLE)), ANSWER);
-    println!("part one: {}", part_one(parse_input(REAL1)));
-    println!("part two: {}", part_one(parse_input(REAL2)));
+    #[test]
+    fn inconsistent() {
+        // t + 1 = 0 mod 4 wants t odd, t + 2 = 0 mod 6 wants it even
+        let discs = vec![disc(1, 4, 0), disc(2, 6, 0)];
+        assert_eq!(brute_force(&discs), None);
+        assert_eq!(part_one(discs), Err(CrtError::Inconsistent));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_input("Disc #1 has five positions; at time=0, it is at position 4.").is_err());
+        assert!(parse_input("Disc #1 has 99999999999999999999 positions; at time=0, it is at position 4.").is_err());
+        assert!(parse_input("Disc #1 has 0 positions; at time=0, it is at position 0.").is_err());
+    }
 }
@@ -0,0 +1,121 @@
+// The filled disk is the seed `a` and its reversed complement `b` alternating, a d1 b d2 a d3 b
+// ..., where the separators d_i are the dragon curve bits. Reducing a chunk of 2^k bits down to a
+// single checksum bit yields 1 exactly when the chunk holds an even number of ones, so the
+// checksum only needs the parity of ones in each chunk, which the structure above gives
+// directly without ever building the disk.
+
+// the number of ones among d_1 ..= d_m, where d_i is 1 when the odd part of i is 3 mod 4
+pub fn dragon_ones(m: u64) -> u64 {
+    let mut total = 0;
+    let mut scaled = m;
+
+    while scaled > 0 {
+        // odd j <= scaled with j = 3 mod 4
+        total += (scaled + 1) / 4;
+        scaled >>= 1;
+    }
+
+    total
+}
+
+pub struct Disk {
+    seed_len: u64,
+    seed_ones: u64,
+    // prefix[i] is the number of ones in the first i bits of the seed
+    prefix: Vec<u64>,
+}
+
+impl Disk {
+    pub fn new(seed: &str) -> Self {
+        let mut prefix = vec![0];
+        for ch in seed.chars() {
+            let last = *prefix.last().unwrap();
+            prefix.push(last + if ch == '1' { 1 } else { 0 });
+        }
+
+        Disk {
+            seed_len: seed.len() as u64,
+            // unwrap: prefix always has its leading zero
+            seed_ones: *prefix.last().unwrap(),
+            prefix,
+        }
+    }
+
+    // the number of ones in the first `p` bits of the filled disk
+    pub fn ones_before(&self, p: u64) -> u64 {
+        let unit = self.seed_len + 1;
+        let (full, rem) = (p / unit, p % unit);
+
+        let a_blocks = full.div_ceil(2);
+        let b_blocks = full / 2;
+        let mut ones = a_blocks * self.seed_ones + b_blocks * (self.seed_len - self.seed_ones) + dragon_ones(full);
+
+        if full.is_multiple_of(2) {
+            ones += self.prefix[rem as usize];
+        } else {
+            // the first `rem` bits of b complement the last `rem` bits of a
+            let tail = self.seed_ones - self.prefix[(self.seed_len - rem) as usize];
+            ones += rem - tail;
+        }
+
+        ones
+    }
+
+    // the checksum of the first `length` bits, one bit at a time
+    pub fn checksum(&self, length: u64) -> impl Iterator<Item = bool> + '_ {
+        assert!(length > 0 && length.is_multiple_of(2), "the disk length must be even");
+        assert!(self.seed_len > 0, "the seed must not be empty");
+
+        let chunk = 1 << length.trailing_zeros();
+
+        (0..length / chunk).map(move |idx| {
+            let ones = self.ones_before((idx + 1) * chunk) - self.ones_before(idx * chunk);
+            ones.is_multiple_of(2)
+        })
+    }
+}
+
+pub fn checksum(seed: &str, length: u64) -> String {
+    Disk::new(seed).checksum(length).map(|b| if b { '1' } else { '0' }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dragon_bit(i: u64) -> bool {
+        let odd = i >> i.trailing_zeros();
+        odd % 4 == 3
+    }
+
+    #[test]
+    fn dragon_bits() {
+        let bits: String = (1..=16).map(|i| if dragon_bit(i) { '1' } else { '0' }).collect();
+        assert_eq!(bits, "0010011000110110");
+
+        for m in 0..200 {
+            assert_eq!(dragon_ones(m), (1..=m).filter(|&i| dragon_bit(i)).count() as u64);
+        }
+    }
+
+    #[test]
+    fn prefix_ones() {
+        let filled = ::extend_data("10010", 200);
+        let disk = Disk::new("10010");
+
+        for p in 0..=200 {
+            let expected = filled[..p].chars().filter(|&c| c == '1').count() as u64;
+            assert_eq!(disk.ones_before(p as u64), expected, "p={}", p);
+        }
+    }
+
+    #[test]
+    fn matches_materialized() {
+        for seed in &["10000", "1", "0", "110", "00101000101111010"] {
+            for length in (2..300).step_by(2) {
+                let expected = ::checksum(&::extend_data(seed, length));
+                assert_eq!(checksum(seed, length as u64), expected, "seed={} length={}", seed, length);
+            }
+        }
+    }
+}
@@ -2,6 +2,8 @@ extern crate itertools;
 
 use itertools::Itertools;
 
+mod dragon;
+
 const SAMPLE : (&'static str, usize) = ("10000", 20);
 const ANSWER : &'static str = "01100";
 
@@ -32,5 +34,8 @@ fn part_one(input: &str, length: usize) -> String {
 
 fn main() {
     assert_eq!(&part_one(SAMPLE.0, SAMPLE.1), ANSWER);
+    assert_eq!(&dragon::checksum(SAMPLE.0, SAMPLE.1 as u64), ANSWER);
     println!("part one: {}", part_one(REAL, 272));
+    println!("part one (streamed): {}", dragon::checksum(REAL, 272));
+    println!("part two: {}", dragon::checksum(REAL, 35651584));
 }
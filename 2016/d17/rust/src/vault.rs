@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+
+use md5::{Digest, Md5};
+use rayon::prelude::*;
+
+// the doors in the order their states appear in the hash
+const DOORS: [(u8, isize, isize); 4] = [(b'U', 0, -1), (b'D', 0, 1), (b'L', -1, 0), (b'R', 1, 0)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vault {
+    width: usize,
+    height: usize,
+    target: (usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    pub shortest: Option<String>,
+    pub longest: Option<usize>,
+}
+
+// a room we're standing in, along with the hasher already fed the passcode and the path so far
+#[derive(Clone)]
+struct State {
+    x: usize,
+    y: usize,
+    hasher: Md5,
+}
+
+impl Vault {
+    // a vault with its target in the bottom right corner
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(width > 0 && height > 0, "a vault needs at least one room");
+        Vault { width, height, target: (width - 1, height - 1) }
+    }
+
+    pub fn with_target(mut self, target: (usize, usize)) -> Self {
+        assert!(target.0 < self.width && target.1 < self.height, "the target must be inside the vault");
+        self.target = target;
+        self
+    }
+
+    fn start(&self, passcode: &str) -> State {
+        let mut hasher = Md5::new();
+        hasher.update(passcode.as_bytes());
+        State { x: 0, y: 0, hasher }
+    }
+
+    fn is_target(&self, state: &State) -> bool {
+        (state.x, state.y) == self.target
+    }
+
+    // the rooms reachable through the doors that are open from `state`, in hash order
+    fn moves<'a>(&'a self, state: &'a State) -> impl Iterator<Item = (u8, State)> + 'a {
+        let hash = state.hasher.clone().finalize();
+        let locks = [hash[0] >> 4, hash[0] & 0xf, hash[1] >> 4, hash[1] & 0xf];
+
+        DOORS.iter().zip(locks).filter_map(move |(&(door, dx, dy), lock)| {
+            let x = state.x.checked_add_signed(dx).filter(|&x| x < self.width)?;
+            let y = state.y.checked_add_signed(dy).filter(|&y| y < self.height)?;
+            if lock < 0xb {
+                return None;
+            }
+
+            let mut hasher = state.hasher.clone();
+            hasher.update([door]);
+            Some((door, State { x, y, hasher }))
+        })
+    }
+
+    // breadth-first, so the first path to reach the target is a shortest one
+    pub fn shortest(&self, passcode: &str) -> Option<String> {
+        let mut queue = VecDeque::from([(self.start(passcode), vec![])]);
+
+        while let Some((state, path)) = queue.pop_front() {
+            if self.is_target(&state) {
+                // unwrap: the path is only ever made of door letters
+                return Some(String::from_utf8(path).unwrap());
+            }
+            for (door, next) in self.moves(&state) {
+                let mut path = path.clone();
+                path.push(door);
+                queue.push_back((next, path));
+            }
+        }
+
+        None
+    }
+
+    // the shortest path from the breadth-first search and the longest from the exhaustive one
+    pub fn explore(&self, passcode: &str) -> Paths {
+        Paths { shortest: self.shortest(passcode), longest: self.longest(passcode) }
+    }
+
+    // the length of the longest path, which means trying every path: the search walks one room
+    // at a time until the paths first branch, then runs a depth-first search from each branch
+    // in parallel
+    pub fn longest(&self, passcode: &str) -> Option<usize> {
+        let mut frontier = vec![(self.start(passcode), 0)];
+
+        while frontier.len() == 1 {
+            // unwrap: the frontier holds exactly one state
+            let (state, len) = frontier.pop().unwrap();
+            if self.is_target(&state) {
+                return Some(len);
+            }
+            frontier = self.moves(&state).map(|(_, next)| (next, len + 1)).collect();
+        }
+
+        frontier.into_par_iter()
+            .filter_map(|(state, len)| self.depth_first(state, len))
+            .max()
+    }
+
+    // every path onwards from `state`, returning the length of the longest to reach the target
+    fn depth_first(&self, state: State, len: usize) -> Option<usize> {
+        let mut longest = None;
+        let mut stack = vec![(state, len)];
+
+        while let Some((state, len)) = stack.pop() {
+            if self.is_target(&state) {
+                longest = longest.max(Some(len));
+                continue;
+            }
+            stack.extend(self.moves(&state).map(|(_, next)| (next, len + 1)));
+        }
+
+        longest
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SAMPLES: [(&str, &str, usize); 3] = [
+        ("ihgpwlah", "DDRRRD", 370),
+        ("kglvqrro", "DDUDRLRRUDRD", 492),
+        ("ulqzkmiv", "DRURDRUDDLLDLUURRDULRLDUUDDDRR", 830),
+    ];
+
+    #[test]
+    fn samples() {
+        let vault = Vault::new(4, 4);
+        for &(passcode, shortest, longest) in SAMPLES.iter() {
+            assert_eq!(vault.shortest(passcode).as_deref(), Some(shortest));
+            assert_eq!(vault.longest(passcode), Some(longest));
+            assert_eq!(vault.explore(passcode), Paths { shortest: Some(shortest.to_string()), longest: Some(longest) });
+        }
+    }
+
+    #[test]
+    fn sealed() {
+        let vault = Vault::new(4, 4);
+        assert_eq!(vault.explore("hijkl"), Paths { shortest: None, longest: None });
+        assert_eq!(vault.shortest("hijkl"), None);
+    }
+
+    #[test]
+    fn other_shapes() {
+        let vault = Vault::new(1, 1);
+        assert_eq!(vault.explore("hijkl"), Paths { shortest: Some(String::new()), longest: Some(0) });
+
+        let vault = Vault::new(6, 3).with_target((2, 1));
+        let paths = vault.explore("ihgpwlah");
+        assert_eq!(paths.shortest, vault.shortest("ihgpwlah"));
+        if let (Some(shortest), Some(longest)) = (paths.shortest, paths.longest) {
+            assert!(shortest.len() <= longest);
+        }
+    }
+}
@@ -1,7 +1,11 @@
+mod traps;
+
+use traps::Row;
 
 const TEST: &'static str = include_str!("../../test");
 const INPUT: &'static str = include_str!("../../input");
 
+#[cfg(test)]
 fn compute(line: &[u8], rounds: usize) -> usize {
     let mut ret = line.iter().filter(|ch| **ch == b'.').count();
 
@@ -30,8 +34,11 @@ fn compute(line: &[u8], rounds: usize) -> usize {
     ret
 }
 
-fn main() {
-    println!("part1 (test): {}", compute(TEST.trim().as_bytes(), 10));
-    println!("part1 (actual): {}", compute(INPUT.trim().as_bytes(), 40));
-    println!("part2 (actual): {}", compute(INPUT.trim().as_bytes(), 400000));
+fn main() -> Result<(), String> {
+    let test: Row = TEST.trim().parse()?;
+    let input: Row = INPUT.trim().parse()?;
+    println!("part1 (test): {}", traps::safe_tiles(&test, 10));
+    println!("part1 (actual): {}", traps::safe_tiles(&input, 40));
+    println!("part2 (actual): {}", traps::safe_tiles(&input, 400000));
+    Ok( () )
 }
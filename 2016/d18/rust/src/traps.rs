@@ -0,0 +1,233 @@
+use std::fmt;
+use std::str::FromStr;
+
+// A tile is a trap exactly when its left and right neighbours differ (the walls count as safe),
+// so with traps as set bits the next row is (row << 1) ^ (row >> 1), i.e. rule 90.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Row {
+    width: usize,
+    words: Vec<u64>,
+}
+
+impl Row {
+    pub fn is_trap(&self, idx: usize) -> bool {
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    pub fn traps(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn safe(&self) -> usize {
+        self.width - self.traps()
+    }
+
+    pub fn next(&self) -> Row {
+        let len = self.words.len();
+
+        let mut words: Vec<u64> = (0..len).map(|w| {
+            // bit i takes its left neighbour i - 1 ...
+            let from_left = (self.words[w] << 1) | if w > 0 { self.words[w - 1] >> 63 } else { 0 };
+            // ... and its right neighbour i + 1
+            let from_right = (self.words[w] >> 1) | if w + 1 < len { self.words[w + 1] << 63 } else { 0 };
+            from_left ^ from_right
+        }).collect();
+
+        if let Some(last) = words.last_mut() {
+            if !self.width.is_multiple_of(64) {
+                *last &= (1 << (self.width % 64)) - 1;
+            }
+        }
+
+        Row { width: self.width, words }
+    }
+}
+
+impl FromStr for Row {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.len();
+        let mut words = vec![0u64; width.div_ceil(64)];
+
+        for (idx, ch) in s.chars().enumerate() {
+            match ch {
+                '^' => words[idx / 64] |= 1 << (idx % 64),
+                '.' => {}
+                _ => return Err(format!("unexpected tile '{}' at {}", ch, idx)),
+            }
+        }
+
+        Ok(Row { width, words })
+    }
+}
+
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for idx in 0..self.width {
+            write!(f, "{}", if self.is_trap(idx) { '^' } else { '.' })?;
+        }
+        Ok(())
+    }
+}
+
+// the number of safe tiles across `rounds` rows, stepping every row
+pub fn safe_tiles_direct(first: &Row, rounds: usize) -> usize {
+    walk(&mut first.clone(), rounds)
+}
+
+// counts the safe tiles in the next `rounds` rows, leaving `row` on the one after them
+fn walk(row: &mut Row, rounds: usize) -> usize {
+    let mut total = 0;
+
+    for _ in 0..rounds {
+        total += row.safe();
+        *row = row.next();
+    }
+
+    total
+}
+
+// Brent's algorithm over `Row::next`, holding only a couple of rows at a time: the index of the
+// first row that repeats and the period it repeats with, if both show up within `limit` steps.
+// Otherwise the hare has already stepped through all `limit` rows, so the error is the number of
+// safe tiles it saw on the way.
+fn find_cycle(first: &Row, limit: usize) -> Result<(usize, usize), usize> {
+    if limit == 0 {
+        return Err(0);
+    }
+
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = first.clone();
+    let mut hare = first.next();
+    let mut steps = 1;
+    // safe tiles on every row before the hare's
+    let mut safe = first.safe();
+
+    while tortoise != hare {
+        if steps >= limit {
+            return Err(safe);
+        }
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        safe += hare.safe();
+        hare = hare.next();
+        period += 1;
+        steps += 1;
+    }
+
+    // a hare `period` rows ahead meets the tortoise exactly where the cycle starts
+    let mut tortoise = first.clone();
+    let mut hare = first.clone();
+    for _ in 0..period {
+        hare = hare.next();
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = tortoise.next();
+        hare = hare.next();
+        start += 1;
+    }
+
+    Ok((start, period))
+}
+
+// the number of safe tiles across `rounds` rows; once a row repeats, whole cycles are skipped.
+// Rows that don't cycle within `rounds` are counted by the cycle search itself, so memory stays
+// constant and no row is stepped twice either way.
+pub fn safe_tiles(first: &Row, rounds: usize) -> usize {
+    let (start, period) = match find_cycle(first, rounds) {
+        Ok((start, _)) if start >= rounds => return safe_tiles_direct(first, rounds),
+        Ok(cycle) => cycle,
+        Err(safe) => return safe,
+    };
+
+    let mut row = first.clone();
+    let lead = walk(&mut row, start);
+    let (cycles, rem) = ((rounds - start) / period, (rounds - start) % period);
+    if cycles == 0 {
+        return lead + walk(&mut row, rem);
+    }
+
+    // walking a whole period brings `row` back to where it started
+    let cycle = walk(&mut row, period);
+    lead + cycles * cycle + walk(&mut row, rem)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sample_rows() {
+        let row: Row = ".^^.^.^^^^".parse().unwrap();
+        let rows: Vec<String> = std::iter::successors(Some(row), |r| Some(r.next()))
+            .take(3)
+            .map(|r| r.to_string())
+            .collect();
+
+        assert_eq!(rows, vec![".^^.^.^^^^", "^^^...^..^", "^.^^.^.^^."]);
+    }
+
+    #[test]
+    fn sample_count() {
+        let row: Row = ".^^.^.^^^^".parse().unwrap();
+        assert_eq!(safe_tiles(&row, 10), 38);
+        assert_eq!(safe_tiles_direct(&row, 10), 38);
+    }
+
+    #[test]
+    fn wide_rows_cross_words() {
+        let raw: String = (0..150).map(|i| if i % 7 == 0 || i % 11 == 3 { '^' } else { '.' }).collect();
+        let row: Row = raw.parse().unwrap();
+
+        for rounds in [1, 2, 40, 333] {
+            assert_eq!(safe_tiles_direct(&row, rounds), crate::compute(raw.as_bytes(), rounds));
+        }
+    }
+
+    #[test]
+    fn skipping_cycles() {
+        // short rows cycle quickly
+        for raw in [".^^.^", "^", "^..^^.^", "......"] {
+            let row: Row = raw.parse().unwrap();
+            for rounds in [1, 5, 17, 100, 1234] {
+                assert_eq!(safe_tiles(&row, rounds), safe_tiles_direct(&row, rounds), "{} x {}", raw, rounds);
+            }
+        }
+    }
+
+    #[test]
+    fn cycle_bounds() {
+        let row: Row = "^..^^.^".parse().unwrap();
+        let (start, period) = find_cycle(&row, 1000).unwrap();
+        let ahead = (0..start + period).fold(row.clone(), |r, _| r.next());
+        let at_start = (0..start).fold(row.clone(), |r, _| r.next());
+        assert_eq!(ahead, at_start);
+
+        // nothing repeats within a handful of rows on the sample's width
+        let sample: Row = ".^^.^.^^^^".parse().unwrap();
+        assert!(find_cycle(&sample, 3).is_err());
+    }
+
+    #[test]
+    fn counts_without_a_cycle() {
+        let sample: Row = ".^^.^.^^^^".parse().unwrap();
+        for rounds in [0, 1, 3, 10] {
+            assert_eq!(find_cycle(&sample, rounds), Err(safe_tiles_direct(&sample, rounds)));
+        }
+    }
+
+    #[test]
+    fn huge_round_counts() {
+        for raw in ["^..^^.^", ".^^.^.^^^^.^"] {
+            let row: Row = raw.parse().unwrap();
+            let rounds = 3_000_017;
+            assert_eq!(safe_tiles(&row, rounds), safe_tiles_direct(&row, rounds), "{}", raw);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::VecDeque;
+
+// The elves are numbered from 1 and the first elf to act is elf 1. Every function returns the
+// number of the last elf standing.
+
+// the largest p with base^p <= n, computed without floating point
+pub fn floor_log(n: usize, base: usize) -> u32 {
+    assert!(n > 0 && base > 1, "floor_log needs n > 0 and base > 1");
+
+    let mut power = 1usize;
+    let mut exp = 0;
+    while let Some(next) = power.checked_mul(base) {
+        if next > n {
+            break;
+        }
+        power = next;
+        exp += 1;
+    }
+    exp
+}
+
+// every k-th elf is eliminated, via J(i) = (J(i - 1) + k) mod i in O(n)
+pub fn every_kth(n: usize, k: usize) -> usize {
+    assert!(n > 0 && k > 0);
+
+    (2..=n).fold(0, |j, i| (j + k) % i) + 1
+}
+
+// the same answer in O(k log n): each pass around the circle removes n / k elves at once. The
+// passes are unwound from a stack of circle sizes, and once k outgrows the circle the O(n)
+// recurrence finishes the job, so nothing here recurses.
+pub fn every_kth_fast(n: usize, k: usize) -> usize {
+    assert!(n > 0 && k > 0);
+
+    if k == 1 {
+        return n;
+    }
+
+    let mut sizes = vec![];
+    let mut m = n;
+    while m > 1 && k <= m {
+        sizes.push(m);
+        m -= m / k;
+    }
+
+    // zero-based survivor of the smallest circle, which has fewer than k elves
+    let mut j = (2..=m).fold(0, |j, i| (j + k) % i);
+
+    for &size in sizes.iter().rev() {
+        let rest = j as isize - (size % k) as isize;
+        j = if rest < 0 {
+            (rest + size as isize) as usize
+        } else {
+            let rest = rest as usize;
+            rest + rest / (k - 1)
+        };
+    }
+
+    j + 1
+}
+
+// each elf in turn eliminates the elf directly across the circle (the nearer-left one when two
+// are equally far); the halves of the circle live in two deques so the victim is always at an end
+pub fn across(n: usize) -> usize {
+    assert!(n > 0);
+
+    let mut left: VecDeque<usize> = (1..=n / 2).collect();
+    let mut right: VecDeque<usize> = (n / 2 + 1..=n).collect();
+
+    while left.len() + right.len() > 1 {
+        if left.len() > right.len() {
+            left.pop_back();
+        } else {
+            right.pop_front();
+        }
+
+        // the elf who just acted moves to the back, and the halves rebalance around the circle
+        if let Some(actor) = left.pop_front() {
+            right.push_back(actor);
+        }
+        if let Some(front) = right.pop_front() {
+            left.push_back(front);
+        }
+    }
+
+    // unwrap: exactly one elf remains in one of the halves
+    left.front().or(right.front()).copied().unwrap()
+}
+
+// closed form for `across`, in terms of the largest power of three not above n
+pub fn across_closed(n: usize) -> usize {
+    let power = 3usize.pow(floor_log(n, 3));
+    let remainder = n - power;
+
+    if remainder == 0 {
+        power
+    } else if remainder <= power {
+        remainder
+    } else {
+        power + (2 * (remainder - power))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Rule {
+        EveryKth(usize),
+        Across,
+    }
+
+    // removes elves one at a time from an explicit circle; quadratic, for checking the rest
+    fn brute_force(n: usize, rule: Rule) -> usize {
+        let mut circle: Vec<usize> = (1..=n).collect();
+        let mut current = 0;
+
+        while circle.len() > 1 {
+            match rule {
+                Rule::EveryKth(k) => {
+                    let victim = (current + k - 1) % circle.len();
+                    circle.remove(victim);
+                    current = victim % circle.len();
+                }
+                Rule::Across => {
+                    let victim = (current + circle.len() / 2) % circle.len();
+                    circle.remove(victim);
+                    if victim < current {
+                        current -= 1;
+                    }
+                    current = (current + 1) % circle.len();
+                }
+            }
+        }
+
+        circle[0]
+    }
+
+    #[test]
+    fn exact_log() {
+        assert_eq!(floor_log(1, 2), 0);
+        assert_eq!(floor_log(8, 2), 3);
+        assert_eq!(floor_log(242, 3), 4);
+        assert_eq!(floor_log(243, 3), 5);
+        assert_eq!(floor_log(usize::MAX, 2), usize::BITS - 1);
+        // a floating-point log puts log3(243) just under 5 and log3(3^33 - 1) at exactly 33
+        let p = 3usize.pow(33);
+        assert_eq!(floor_log(p - 1, 3), 32);
+        assert_eq!(floor_log(p, 3), 33);
+    }
+
+    #[test]
+    fn every_kth_agrees() {
+        for n in 1..200 {
+            for k in 1..8 {
+                let expected = brute_force(n, Rule::EveryKth(k));
+                assert_eq!(every_kth(n, k), expected, "n={} k={}", n, k);
+                assert_eq!(every_kth_fast(n, k), expected, "n={} k={}", n, k);
+            }
+        }
+    }
+
+    #[test]
+    fn every_kth_large() {
+        // k far beyond n used to recurse once per elf
+        assert_eq!(every_kth_fast(1_000_000, 1 << 40), every_kth(1_000_000, 1 << 40));
+        assert_eq!(every_kth_fast(3_005_290, 3_000_000), every_kth(3_005_290, 3_000_000));
+        assert_eq!(every_kth_fast(10_000_000, 3), every_kth(10_000_000, 3));
+    }
+
+    #[test]
+    fn across_agrees() {
+        for n in 1..300 {
+            let expected = brute_force(n, Rule::Across);
+            assert_eq!(across(n), expected, "n={}", n);
+            assert_eq!(across_closed(n), expected, "n={}", n);
+        }
+    }
+
+    #[test]
+    fn sample() {
+        assert_eq!(every_kth(5, 2), 3);
+        assert_eq!(across(5), 2);
+    }
+}
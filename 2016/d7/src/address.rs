@@ -0,0 +1,129 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    // outside square brackets
+    Supernet,
+    // inside square brackets
+    Hypernet,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub kind: Kind,
+    pub text: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressError {
+    // a '[' inside a hypernet sequence
+    NestedBracket(usize),
+    // a ']' with no '[' before it
+    UnopenedBracket(usize),
+    // the address ends inside a hypernet sequence
+    Unclosed(usize),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AddressError::NestedBracket(at) => write!(f, "nested '[' at {}", at),
+            AddressError::UnopenedBracket(at) => write!(f, "']' without '[' at {}", at),
+            AddressError::Unclosed(at) => write!(f, "'[' at {} is never closed", at),
+        }
+    }
+}
+
+// splits an address into its segments in a single pass, skipping empty ones
+pub struct Segments<'a> {
+    input: &'a [u8],
+    pos: usize,
+    kind: Kind,
+    // where the hypernet we're in was opened
+    opened: usize,
+}
+
+impl<'a> Segments<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Segments { input: input.as_bytes(), pos: 0, kind: Kind::Supernet, opened: 0 }
+    }
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = Result<Segment<'a>, AddressError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos <= self.input.len() {
+            let start = self.pos;
+            let end = self.input[start..].iter()
+                .position(|&b| b == b'[' || b == b']')
+                .map_or(self.input.len(), |n| start + n);
+            let segment = Segment { kind: self.kind, text: &self.input[start..end] };
+            self.pos = end + 1;
+
+            match (self.input.get(end), self.kind) {
+                (Some(b'['), Kind::Supernet) => {
+                    self.kind = Kind::Hypernet;
+                    self.opened = end;
+                }
+                (Some(b']'), Kind::Hypernet) => self.kind = Kind::Supernet,
+                (Some(b'['), Kind::Hypernet) => return Some(Err(AddressError::NestedBracket(end))),
+                // segments only end at brackets, so this is a ']' outside of one
+                (Some(_), _) => return Some(Err(AddressError::UnopenedBracket(end))),
+                (None, Kind::Hypernet) => return Some(Err(AddressError::Unclosed(self.opened))),
+                (None, Kind::Supernet) => {}
+            }
+
+            if !segment.text.is_empty() {
+                return Some(Ok(segment));
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address<'a> {
+    segments: Vec<Segment<'a>>,
+}
+
+impl<'a> Address<'a> {
+    pub fn parse(input: &'a str) -> Result<Self, AddressError> {
+        Ok(Address { segments: Segments::new(input).collect::<Result<_, _>>()? })
+    }
+
+    pub fn segments(&self, kind: Kind) -> impl Iterator<Item = &'a [u8]> + '_ {
+        self.segments.iter().filter(move |s| s.kind == kind).map(|s| s.text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<(Kind, &str)> {
+        Segments::new(input)
+            .map(|s| s.map(|s| (s.kind, ::std::str::from_utf8(s.text).unwrap())))
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn tokenize() {
+        assert_eq!(kinds("abba[mnop]qrst"), vec![
+            (Kind::Supernet, "abba"),
+            (Kind::Hypernet, "mnop"),
+            (Kind::Supernet, "qrst"),
+        ]);
+        assert_eq!(kinds("[ab]cd[]"), vec![(Kind::Hypernet, "ab"), (Kind::Supernet, "cd")]);
+        assert_eq!(kinds(""), vec![]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Address::parse("ab[cd[ef]]"), Err(AddressError::NestedBracket(5)));
+        assert_eq!(Address::parse("ab]cd"), Err(AddressError::UnopenedBracket(2)));
+        assert_eq!(Address::parse("ab[cd]ef[gh"), Err(AddressError::Unclosed(8)));
+    }
+}
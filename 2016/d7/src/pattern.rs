@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use address::{Address, Kind};
+
+// the two letters of a matched pattern, e.g. ('a', 'b') for both "abba" and "aba"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pair {
+    pub outer: u8,
+    pub inner: u8,
+}
+
+impl Pair {
+    // the pair with its letters swapped, so an ABA's BAB
+    pub fn inverse(&self) -> Pair {
+        Pair { outer: self.inner, inner: self.outer }
+    }
+}
+
+// a palindromic pair of length n: one letter at both ends and a different letter repeated in
+// between, so n = 4 is an ABBA, n = 3 an ABA and n = 5 an ABBBA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pattern {
+    len: usize,
+}
+
+pub const ABBA: Pattern = Pattern::new(4);
+pub const ABA: Pattern = Pattern::new(3);
+
+impl Pattern {
+    pub const fn new(len: usize) -> Self {
+        assert!(len >= 3, "a palindromic pair needs at least one inner letter");
+        Pattern { len }
+    }
+
+    fn matches_window(&self, w: &[u8]) -> Option<Pair> {
+        let (outer, inner) = (w[0], w[1]);
+        let middle = &w[1..self.len - 1];
+
+        if outer != inner && w[self.len - 1] == outer && middle.iter().all(|&b| b == inner) {
+            Some(Pair { outer, inner })
+        } else {
+            None
+        }
+    }
+
+    // every occurrence in `text`, overlapping ones included
+    pub fn find<'a>(&'a self, text: &'a [u8]) -> impl Iterator<Item = Pair> + 'a {
+        text.windows(self.len).filter_map(move |w| self.matches_window(w))
+    }
+
+    pub fn occurs_in(&self, text: &[u8]) -> bool {
+        self.find(text).next().is_some()
+    }
+
+    // true if some segment of the kind contains the pattern
+    pub fn in_any(&self, address: &Address, kind: Kind) -> bool {
+        address.segments(kind).any(|s| self.occurs_in(s))
+    }
+
+    // true if a pair found in a `from` segment shows up inverted in a `to` segment
+    pub fn corresponds(&self, address: &Address, from: Kind, to: Kind) -> bool {
+        let found: HashSet<Pair> = address.segments(from).flat_map(|s| self.find(s)).collect();
+        !found.is_empty() && address.segments(to).any(|s| self.find(s).any(|p| found.contains(&p.inverse())))
+    }
+}
+
+// transport-layer snooping: an ABBA outside the brackets and none inside
+pub fn supports_tls(address: &Address) -> bool {
+    ABBA.in_any(address, Kind::Supernet) && !ABBA.in_any(address, Kind::Hypernet)
+}
+
+// super-secret listening: an ABA outside the brackets with its BAB inside
+pub fn supports_ssl(address: &Address) -> bool {
+    ABA.corresponds(address, Kind::Supernet, Kind::Hypernet)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check(input: &str, f: fn(&Address) -> bool) -> Vec<bool> {
+        input.lines().map(|l| f(&Address::parse(l).unwrap())).collect()
+    }
+
+    #[test]
+    fn tls() {
+        assert_eq!(check(::SAMPLE1, supports_tls), [true, false, false, true]);
+    }
+
+    #[test]
+    fn ssl() {
+        assert_eq!(check(::SAMPLE2, supports_ssl), [true, false, true, true]);
+    }
+
+    #[test]
+    fn longer_patterns() {
+        let p = Pattern::new(5);
+        assert_eq!(p.find(b"xabbbaxbaaab").collect::<Vec<_>>(), [
+            Pair { outer: b'a', inner: b'b' },
+            Pair { outer: b'b', inner: b'a' },
+        ]);
+        assert!(!p.occurs_in(b"abcba"));
+        assert!(!p.occurs_in(b"aaaaa"));
+
+        let address = Address::parse("qabbbaq[xbaaabx]").unwrap();
+        assert!(p.corresponds(&address, Kind::Supernet, Kind::Hypernet));
+        assert!(p.corresponds(&address, Kind::Hypernet, Kind::Supernet));
+        assert!(!ABA.corresponds(&address, Kind::Supernet, Kind::Hypernet));
+    }
+}
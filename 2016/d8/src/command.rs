@@ -0,0 +1,87 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Rect(usize, usize),
+    RotateRow(usize, usize),
+    RotateCol(usize, usize),
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Command::Rect(w, h) => write!(f, "rect {}x{}", w, h),
+            Command::RotateRow(y, by) => write!(f, "rotate row y={} by {}", y, by),
+            Command::RotateCol(x, by) => write!(f, "rotate column x={} by {}", x, by),
+        }
+    }
+}
+
+peg::parser! {
+    grammar commands() for str {
+        pub rule parse() -> Vec<Command>
+            = whitespace() c:command()* { c }
+
+        rule command() -> Command
+            = rect()
+            / rotate_row()
+            / rotate_col()
+
+        rule rect() -> Command
+            = "rect" gap() w:number() "x" h:number() whitespace() { Command::Rect(w, h) }
+
+        rule rotate_row() -> Command
+            = "rotate" gap() "row" gap() "y=" y:number() gap() "by" gap() n:number() whitespace() {
+                Command::RotateRow(y, n)
+            }
+
+        rule rotate_col() -> Command
+            = "rotate" gap() "column" gap() "x=" x:number() gap() "by" gap() n:number() whitespace() {
+                Command::RotateCol(x, n)
+            }
+
+        rule number() -> usize
+            = n:$(['0'..='9']+) {? n.parse().or(Err("number")) }
+
+        rule whitespace()
+            = [' ' | '\t' | '\r' | '\n']*
+
+        // the blanks that have to separate words on a line
+        rule gap()
+            = [' ' | '\t']+
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Command>, String> {
+    commands::parse(input).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        assert_eq!(parse(::SAMPLE1), Ok(vec![
+            Command::Rect(3, 2),
+            Command::RotateCol(1, 1),
+            Command::RotateRow(0, 4),
+            Command::RotateCol(1, 1),
+        ]));
+    }
+
+    #[test]
+    fn round_trip() {
+        let cmds = parse(::SAMPLE1).unwrap();
+        let text: String = cmds.iter().map(|c| format!("{}\r\n", c)).collect();
+        assert_eq!(parse(&text), Ok(cmds));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse("rect 3x").unwrap_err().contains("1:8"));
+        assert!(parse("rotate row x=1 by 2").is_err());
+        assert!(parse("rect3x2").is_err());
+        assert!(parse("rotate rowy=0 by 4").is_err());
+    }
+}
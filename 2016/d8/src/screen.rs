@@ -0,0 +1,302 @@
+use std::fmt;
+use std::str::FromStr;
+
+use render::{Canvas, Render};
+
+use command::Command;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScreenError {
+    // a command reaching past the edge of the screen
+    OutOfBounds(Command),
+    // running backwards, a rect covers a pixel the target needs to be off
+    Conflict { step: usize, x: usize, y: usize },
+    Parse(String),
+}
+
+impl fmt::Display for ScreenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ScreenError::OutOfBounds(cmd) => write!(f, "`{}` does not fit on the screen", cmd),
+            ScreenError::Conflict { step, x, y } =>
+                write!(f, "command {} lights ({}, {}) but the target has it off", step, x, y),
+            ScreenError::Parse(ref msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+// pixels are stored row-major; a pixel of `Option<bool>` is one whose value is unknown when
+// it's `None`, which is what running a rect backwards leaves behind
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Screen<P = bool> {
+    width: usize,
+    height: usize,
+    pixels: Vec<P>,
+}
+
+// everything that could have been on the screen before a list of commands was run
+pub type Preimage = Screen<Option<bool>>;
+
+impl<P: Copy> Screen<P> {
+    pub fn filled(width: usize, height: usize, pixel: P) -> Self {
+        Screen { width, height, pixels: vec![pixel; width * height] }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> P {
+        self.pixels[y * self.width + x]
+    }
+
+    fn check(&self, cmd: Command) -> Result<(), ScreenError> {
+        let fits = match cmd {
+            Command::Rect(w, h) => w <= self.width && h <= self.height,
+            Command::RotateRow(y, _) => y < self.height,
+            Command::RotateCol(x, _) => x < self.width,
+        };
+        if fits { Ok(()) } else { Err(ScreenError::OutOfBounds(cmd)) }
+    }
+
+    // shifts a row or column towards higher indices, wrapping around, without copying it out
+    fn rotate(&mut self, cmd: Command, forwards: bool) {
+        let (start, stride, len, by) = match cmd {
+            Command::RotateRow(y, by) => (y * self.width, 1, self.width, by),
+            Command::RotateCol(x, by) => (x, self.width, self.height, by),
+            Command::Rect(..) => return,
+        };
+        if len == 0 {
+            return;
+        }
+
+        let by = by % len;
+        let by = if forwards { by } else { (len - by) % len };
+
+        if stride == 1 {
+            self.pixels[start..start + len].rotate_right(by);
+        } else {
+            // rotating right by k is reversing the whole run, then its first k and last n - k
+            self.reverse(start, stride, 0, len);
+            self.reverse(start, stride, 0, by);
+            self.reverse(start, stride, by, len);
+        }
+    }
+
+    fn reverse(&mut self, start: usize, stride: usize, lo: usize, hi: usize) {
+        let (mut lo, mut hi) = (lo, hi);
+        while lo + 1 < hi {
+            hi -= 1;
+            self.pixels.swap(start + lo * stride, start + hi * stride);
+            lo += 1;
+        }
+    }
+}
+
+impl Screen<bool> {
+    pub fn new(width: usize, height: usize) -> Self {
+        Screen::filled(width, height, false)
+    }
+
+    pub fn lit(&self) -> usize {
+        self.pixels.iter().filter(|&&p| p).count()
+    }
+
+    pub fn apply(&mut self, cmd: Command) -> Result<(), ScreenError> {
+        self.check(cmd)?;
+
+        match cmd {
+            Command::Rect(w, h) => {
+                for y in 0..h {
+                    for p in &mut self.pixels[y * self.width..y * self.width + w] {
+                        *p = true;
+                    }
+                }
+            }
+            _ => self.rotate(cmd, true),
+        }
+        Ok(())
+    }
+
+    pub fn execute(&mut self, cmds: &[Command]) -> Result<(), ScreenError> {
+        for &cmd in cmds {
+            self.apply(cmd)?;
+        }
+        Ok(())
+    }
+
+    // the screen after each command in turn
+    pub fn frames<'a>(&self, cmds: &'a [Command]) -> Frames<'a> {
+        Frames { screen: self.clone(), cmds: cmds.iter() }
+    }
+
+    // runs `cmds` backwards from this screen, recovering which starting pixels were forced
+    pub fn unexecute(&self, cmds: &[Command]) -> Result<Preimage, ScreenError> {
+        let mut pre = Screen {
+            width: self.width,
+            height: self.height,
+            pixels: self.pixels.iter().map(|&p| Some(p)).collect(),
+        };
+
+        for (step, &cmd) in cmds.iter().enumerate().rev() {
+            pre.unapply(step, cmd)?;
+        }
+        Ok(pre)
+    }
+}
+
+impl Screen<Option<bool>> {
+    // undoes a single command; `step` only labels the error
+    pub fn unapply(&mut self, step: usize, cmd: Command) -> Result<(), ScreenError> {
+        self.check(cmd)?;
+
+        match cmd {
+            Command::Rect(w, h) => {
+                for y in 0..h {
+                    for x in 0..w {
+                        let p = &mut self.pixels[y * self.width + x];
+                        if *p == Some(false) {
+                            return Err(ScreenError::Conflict { step, x, y });
+                        }
+                        // whatever was here before got painted over
+                        *p = None;
+                    }
+                }
+            }
+            _ => self.rotate(cmd, false),
+        }
+        Ok(())
+    }
+
+    // true if running the commands on `screen` gives back the target this came from
+    pub fn admits(&self, screen: &Screen) -> bool {
+        self.width == screen.width && self.height == screen.height &&
+            self.pixels.iter().zip(screen.pixels.iter()).all(|(&p, &s)| p.is_none_or(|p| p == s))
+    }
+
+    // the starting screen with every unconstrained pixel left off
+    pub fn darkest(&self) -> Screen {
+        Screen {
+            width: self.width,
+            height: self.height,
+            pixels: self.pixels.iter().map(|&p| p.unwrap_or(false)).collect(),
+        }
+    }
+}
+
+pub struct Frames<'a> {
+    screen: Screen,
+    cmds: ::std::slice::Iter<'a, Command>,
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<Screen, ScreenError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &cmd = self.cmds.next()?;
+        Some(self.screen.apply(cmd).map(|_| self.screen.clone()))
+    }
+}
+
+// reads lines of '#' and '.', the same way the screen renders
+impl FromStr for Screen {
+    type Err = ScreenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s.lines().map(str::trim_end).filter(|l| !l.is_empty()).collect();
+        let width = rows.first().map_or(0, |r| r.len());
+        let mut pixels = Vec::with_capacity(width * rows.len());
+
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(ScreenError::Parse(format!("row {} is {} wide, expected {}", y, row.len(), width)));
+            }
+            for (x, c) in row.chars().enumerate() {
+                pixels.push(match c {
+                    '#' => true,
+                    '.' => false,
+                    _ => return Err(ScreenError::Parse(format!("unexpected {:?} at ({}, {})", c, x, y))),
+                });
+            }
+        }
+
+        Ok(Screen { width, height: rows.len(), pixels })
+    }
+}
+
+impl Render for Screen {
+    fn render(&self, canvas: &mut Canvas) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                canvas.put(x, y, if self.get(x, y) { '#' } else { '.' });
+            }
+        }
+    }
+}
+
+impl Render for Preimage {
+    fn render(&self, canvas: &mut Canvas) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let glyph = match self.get(x, y) {
+                    Some(true) => '#',
+                    Some(false) => '.',
+                    None => '?',
+                };
+                canvas.put(x, y, glyph);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use command::parse;
+
+    #[test]
+    fn sample_frames() {
+        let cmds = parse(::SAMPLE1).unwrap();
+        let frames: Vec<String> = Screen::new(7, 3).frames(&cmds).map(|f| f.unwrap().text()).collect();
+
+        assert_eq!(frames, [
+            "###....\n###....\n.......\n",
+            "#.#....\n###....\n.#.....\n",
+            "....#.#\n###....\n.#.....\n",
+            ".#..#.#\n#.#....\n.#.....\n",
+        ]);
+    }
+
+    #[test]
+    fn column_rotation_in_place() {
+        let mut screen: Screen = "#..\n...\n.#.\n...\n".parse().unwrap();
+        screen.apply(Command::RotateCol(0, 6)).unwrap();
+        screen.apply(Command::RotateCol(1, 1)).unwrap();
+        assert_eq!(screen.text(), "...\n...\n#..\n.#.\n");
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut screen = Screen::new(7, 3);
+        assert_eq!(screen.apply(Command::Rect(8, 1)), Err(ScreenError::OutOfBounds(Command::Rect(8, 1))));
+        assert!(screen.apply(Command::RotateRow(3, 1)).is_err());
+    }
+
+    #[test]
+    fn inverse() {
+        let cmds = parse(::SAMPLE1).unwrap();
+        let mut screen = Screen::new(7, 3);
+        screen.execute(&cmds).unwrap();
+
+        let pre = screen.unexecute(&cmds).unwrap();
+        assert_eq!(pre.text(), "???....\n???....\n.......\n");
+        assert!(pre.admits(&Screen::new(7, 3)));
+
+        let mut again = pre.darkest();
+        again.execute(&cmds).unwrap();
+        assert_eq!(again, screen);
+    }
+
+    #[test]
+    fn inverse_conflict() {
+        let target: Screen = "...\n...\n".parse().unwrap();
+        let cmds = [Command::RotateRow(0, 1), Command::Rect(1, 1), Command::RotateRow(0, 1)];
+        assert_eq!(target.unexecute(&cmds), Err(ScreenError::Conflict { step: 1, x: 0, y: 0 }));
+    }
+}
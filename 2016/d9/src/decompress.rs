@@ -0,0 +1,336 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufReader, Read};
+
+// markers longer than this are rejected rather than buffered
+const MAX_MARKER: usize = 32;
+// how deeply v2 markers may nest inside each other
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecompressError {
+    // a '(' with no matching ')'
+    Unterminated { offset: usize },
+    // something between the parens that isn't `<length>x<times>`
+    InvalidMarker { offset: usize, marker: String },
+    // a marker that wants to repeat more bytes than are left
+    Overrun { offset: usize, wanted: usize, available: usize },
+    TooDeep { offset: usize },
+    Overflow { offset: usize },
+}
+
+impl DecompressError {
+    pub fn offset(&self) -> usize {
+        match *self {
+            DecompressError::Unterminated { offset } |
+            DecompressError::InvalidMarker { offset, .. } |
+            DecompressError::Overrun { offset, .. } |
+            DecompressError::TooDeep { offset } |
+            DecompressError::Overflow { offset } => offset,
+        }
+    }
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecompressError::Unterminated { offset } =>
+                write!(f, "unterminated marker at byte {}", offset),
+            DecompressError::InvalidMarker { offset, ref marker } =>
+                write!(f, "invalid marker {:?} at byte {}", marker, offset),
+            DecompressError::Overrun { offset, wanted, available } =>
+                write!(f, "marker at byte {} repeats {} bytes but only {} remain", offset, wanted, available),
+            DecompressError::TooDeep { offset } =>
+                write!(f, "markers nested more than {} deep at byte {}", MAX_DEPTH, offset),
+            DecompressError::Overflow { offset } =>
+                write!(f, "decompressed length overflows at byte {}", offset),
+        }
+    }
+}
+
+impl Error for DecompressError {}
+
+impl From<DecompressError> for io::Error {
+    fn from(err: DecompressError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Marker {
+    pub length: usize,
+    pub times: usize,
+}
+
+impl Marker {
+    // parses the text between the parens of a marker starting at `offset`
+    fn parse(body: &[u8], offset: usize) -> Result<Marker, DecompressError> {
+        let invalid = || DecompressError::InvalidMarker {
+            offset,
+            marker: String::from_utf8_lossy(body).into_owned(),
+        };
+
+        let number = |digits: &[u8]| -> Result<usize, DecompressError> {
+            if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+                return Err(invalid());
+            }
+            // unwrap: only ascii digits remain
+            ::std::str::from_utf8(digits).unwrap().parse().map_err(|_| invalid())
+        };
+
+        let split = body.iter().position(|&b| b == b'x').ok_or_else(invalid)?;
+        Ok(Marker {
+            length: number(&body[..split])?,
+            times: number(&body[split + 1..])?,
+        })
+    }
+
+    // finds the marker opening at `input[offset]`, returning it and the offset just past its ')'
+    fn find(input: &[u8], offset: usize) -> Result<(Marker, usize), DecompressError> {
+        let rest = &input[offset + 1..];
+        let close = rest.iter().take(MAX_MARKER + 1).position(|&b| b == b')');
+
+        match close {
+            Some(close) => Ok((Marker::parse(&rest[..close], offset)?, offset + close + 2)),
+            None if rest.len() > MAX_MARKER => Err(DecompressError::InvalidMarker {
+                offset,
+                marker: String::from_utf8_lossy(&rest[..MAX_MARKER]).into_owned(),
+            }),
+            None => Err(DecompressError::Unterminated { offset }),
+        }
+    }
+}
+
+enum State {
+    Literal,
+    // the data of the last marker, with `times` copies still to hand out
+    Repeat { data: Vec<u8>, pos: usize, times: usize },
+}
+
+// expands the version one format as it is read, buffering only the data of one marker at a time
+pub struct Decompressor<R> {
+    inner: io::Bytes<BufReader<R>>,
+    offset: usize,
+    state: State,
+    // an error hit after some output was already produced, reported on the next read
+    pending: Option<io::Error>,
+}
+
+impl<R: Read> Decompressor<R> {
+    pub fn new(inner: R) -> Self {
+        Decompressor { inner: BufReader::new(inner).bytes(), offset: 0, state: State::Literal, pending: None }
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        match self.inner.next() {
+            Some(b) => {
+                self.offset += 1;
+                b.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn read_marker(&mut self) -> io::Result<()> {
+        let start = self.offset - 1;
+        let mut body = Vec::new();
+
+        loop {
+            match self.next_byte()? {
+                Some(b')') => break,
+                Some(b) if body.len() < MAX_MARKER => body.push(b),
+                Some(_) => return Err(DecompressError::InvalidMarker {
+                    offset: start,
+                    marker: String::from_utf8_lossy(&body).into_owned(),
+                }.into()),
+                None => return Err(DecompressError::Unterminated { offset: start }.into()),
+            }
+        }
+
+        let marker = Marker::parse(&body, start)?;
+        // grown as bytes arrive; the marker's length says nothing about how much input is left
+        let mut data = Vec::new();
+        while data.len() < marker.length {
+            match self.next_byte()? {
+                Some(b) => data.push(b),
+                None => return Err(DecompressError::Overrun {
+                    offset: start,
+                    wanted: marker.length,
+                    available: data.len(),
+                }.into()),
+            }
+        }
+
+        if !data.is_empty() && marker.times > 0 {
+            self.state = State::Repeat { data, pos: 0, times: marker.times };
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decompressor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(err) = self.pending.take() {
+            return Err(err);
+        }
+
+        let mut written = 0;
+
+        while written < buf.len() {
+            if let State::Repeat { ref data, ref mut pos, ref mut times } = self.state {
+                let n = (data.len() - *pos).min(buf.len() - written);
+                buf[written..written + n].copy_from_slice(&data[*pos..*pos + n]);
+                written += n;
+                *pos += n;
+                if *pos == data.len() {
+                    *pos = 0;
+                    *times -= 1;
+                }
+                if *times > 0 {
+                    continue;
+                }
+            }
+            self.state = State::Literal;
+
+            match self.next_byte() {
+                Ok(Some(b'(')) => {
+                    if let Err(err) = self.read_marker() {
+                        // hand back what was expanded before the broken marker first
+                        if written == 0 {
+                            return Err(err);
+                        }
+                        self.pending = Some(err);
+                        break;
+                    }
+                }
+                Ok(Some(b)) if b.is_ascii_whitespace() => {}
+                Ok(Some(b)) => {
+                    buf[written] = b;
+                    written += 1;
+                }
+                Ok(None) => break,
+                Err(err) if written == 0 => return Err(err),
+                Err(err) => {
+                    self.pending = Some(err);
+                    break;
+                }
+            }
+        }
+
+        Ok(written)
+    }
+}
+
+// the fully expanded length of the version two format, where marker data is itself expanded;
+// every byte is counted once with the product of the repeat counts of the markers around it
+pub fn decompressed_len(input: &[u8]) -> Result<u64, DecompressError> {
+    // each open marker as the offset its data ends at and the weight inside it
+    let mut stack = [(0usize, 0u64); MAX_DEPTH];
+    let mut depth = 0;
+    let mut total: u64 = 0;
+    let mut i = 0;
+
+    while i < input.len() {
+        while depth > 0 && stack[depth - 1].0 <= i {
+            depth -= 1;
+        }
+        let (end, weight) = if depth > 0 { stack[depth - 1] } else { (input.len(), 1) };
+
+        match input[i] {
+            b'(' => {
+                let (marker, after) = Marker::find(&input[..end], i)?;
+                let available = end - after;
+                if marker.length > available {
+                    return Err(DecompressError::Overrun { offset: i, wanted: marker.length, available });
+                }
+                if depth == MAX_DEPTH {
+                    return Err(DecompressError::TooDeep { offset: i });
+                }
+                let weight = (marker.times as u64).checked_mul(weight)
+                    .ok_or(DecompressError::Overflow { offset: i })?;
+                stack[depth] = (after + marker.length, weight);
+                depth += 1;
+                i = after;
+            }
+            b if b.is_ascii_whitespace() => i += 1,
+            _ => {
+                total = total.checked_add(weight).ok_or(DecompressError::Overflow { offset: i })?;
+                i += 1;
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn expand(input: &str) -> io::Result<String> {
+        let mut out = String::new();
+        Decompressor::new(input.as_bytes()).read_to_string(&mut out)?;
+        Ok(out)
+    }
+
+    fn error(err: io::Error) -> DecompressError {
+        // unwrap: every error from a byte slice comes from the decompressor
+        err.into_inner().unwrap().downcast::<DecompressError>().map(|e| *e).unwrap()
+    }
+
+    #[test]
+    fn version_one() {
+        assert_eq!(expand("A(1x5)BC").unwrap(), "ABBBBBC");
+        assert_eq!(expand("A(2x2)BCD(2x2)EFG").unwrap(), "ABCBCDEFEFG");
+        assert_eq!(expand("X(8x2)(3x3)ABCY").unwrap(), "X(3x3)ABC(3x3)ABCY");
+        assert_eq!(expand("AB\nC(0x3)D\n").unwrap(), "ABCD");
+    }
+
+    #[test]
+    fn small_reads() {
+        let mut dec = Decompressor::new(&b"(3x4)XYZ!"[..]);
+        let mut buf = [0; 5];
+        let mut out = Vec::new();
+        loop {
+            match dec.read(&mut buf).unwrap() {
+                0 => break,
+                n => out.extend_from_slice(&buf[..n]),
+            }
+        }
+        assert_eq!(out, b"XYZXYZXYZXYZ!");
+    }
+
+    #[test]
+    fn output_before_error() {
+        let mut dec = Decompressor::new(&b"ABC(2x"[..]);
+        let mut buf = [0; 8];
+        assert_eq!(dec.read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], b"ABC");
+        assert_eq!(error(dec.read(&mut buf).unwrap_err()), DecompressError::Unterminated { offset: 3 });
+    }
+
+    #[test]
+    fn version_two() {
+        assert_eq!(decompressed_len(b"(27x12)(20x12)(13x14)(7x10)(1x12)A"), Ok(241920));
+        assert_eq!(decompressed_len(b"(25x3)(3x3)ABC(2x3)XY(5x2)PQRSTX(18x9)(3x2)TWO(5x7)SEVEN"), Ok(445));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error(expand("AB(3x2").unwrap_err()), DecompressError::Unterminated { offset: 2 });
+        assert_eq!(error(expand("A(4x2)XY").unwrap_err()),
+                   DecompressError::Overrun { offset: 1, wanted: 4, available: 2 });
+        assert_eq!(error(expand("(3y2)XYZ").unwrap_err()),
+                   DecompressError::InvalidMarker { offset: 0, marker: "3y2".to_string() });
+        assert_eq!(error(expand("(18446744073709551615x2)A").unwrap_err()),
+                   DecompressError::Overrun { offset: 0, wanted: usize::MAX, available: 1 });
+
+        assert_eq!(decompressed_len(b"AB(3x2"), Err(DecompressError::Unterminated { offset: 2 }));
+        assert_eq!(decompressed_len(b"(x2)A").unwrap_err().offset(), 0);
+        assert_eq!(decompressed_len(b"(18446744073709551615x2)A"),
+                   Err(DecompressError::Overrun { offset: 0, wanted: usize::MAX, available: 1 }));
+        // the inner marker reaches past the data of the outer one
+        assert_eq!(decompressed_len(b"(6x2)(3x2)ABCD"),
+                   Err(DecompressError::Overrun { offset: 5, wanted: 3, available: 1 }));
+    }
+}
@@ -1,3 +1,9 @@
+mod decompress;
+
+use std::error::Error;
+use std::io;
+
+use decompress::{DecompressError, Decompressor};
 
 fn load_input() -> String {
     use std::error::Error;
@@ -19,7 +25,7 @@ fn load_input() -> String {
     }
 }
 
-const TEST1 : [(&'static str, usize); 6] = [
+const TEST1 : [(&'static str, u64); 6] = [
     ("ADVENT", 6),
     ("A(1x5)BC", 7),
     ("(3x3)XYZ", 9),
@@ -28,93 +34,30 @@ const TEST1 : [(&'static str, usize); 6] = [
     ("X(8x2)(3x3)ABCY", 18),
 ];
 
-fn part_one(input: &str) -> usize {
-    let mut chars = input.chars();
-    let mut out = String::new();
-    while let Some(c) = chars.next() {
-        match c {
-            '(' => {
-                let mut tmp = String::new();
-                while let Some(c) = chars.next() {
-                    if c == ')' {
-                        break;
-                    } else {
-                        tmp.push(c);
-                    }
-                }
-                println!("captured: {}", tmp);
-                let mut n = tmp.split('x').map(|s| s.parse().unwrap());
-                let run = n.next().unwrap();
-                let times = n.next().unwrap();
-                let mut tmp = String::new();
-                for _ in 0..run {
-                    tmp.push(chars.next().unwrap());
-                }
-                println!("writing {} times: {}", times, tmp);
-                for _ in 0..times {
-                    out.push_str(&tmp);
-                }
-            },
-            'A'...'Z' => {
-                out.push(c);
-            },
-            _ => {},
-        }
-        println!("out: {}", out);
-    }
-    out.len()
+fn part_one(input: &str) -> Result<u64, io::Error> {
+    io::copy(&mut Decompressor::new(input.as_bytes()), &mut io::sink())
 }
 
-const TEST2 : [(&'static str, usize); 4] = [
+const TEST2 : [(&'static str, u64); 4] = [
     ("(3x3)XYZ", 9),
     ("X(8x2)(3x3)ABCY", 20),
     ("(27x12)(20x12)(13x14)(7x10)(1x12)A", 241920),
     ("(25x3)(3x3)ABC(2x3)XY(5x2)PQRSTX(18x9)(3x2)TWO(5x7)SEVEN", 445),
 ];
 
-fn part_two(input: &str) -> usize {
-    let mut chars = input.chars();
-    let mut out = 0;
-    while let Some(c) = chars.next() {
-        match c {
-            '(' => {
-                let mut tmp = String::new();
-                while let Some(c) = chars.next() {
-                    if c == ')' {
-                        break;
-                    } else {
-                        tmp.push(c);
-                    }
-                }
-                let mut n = tmp.split('x').map(|s| s.parse().unwrap());
-                let run = n.next().unwrap();
-                let times = n.next().unwrap();
-                let mut tmp = String::new();
-                for _ in 0..run {
-                    tmp.push(chars.next().unwrap());
-                }
-                let num = if tmp.contains('(') {
-                    part_two(&tmp)
-                } else {
-                    tmp.len()
-                };
-                out += num * times;
-            },
-            'A'...'Z' => out += 1,
-            _ => {},
-        }
-    }
-    out
+fn part_two(input: &str) -> Result<u64, DecompressError> {
+    decompress::decompressed_len(input.as_bytes())
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn Error>> {
     let input = load_input();
     for &(i, o) in TEST1.iter() {
-        assert_eq!(part_one(i), o);
+        assert_eq!(part_one(i)?, o);
     }
-    println!("part one: {}", part_one(&input));
+    println!("part one: {}", part_one(&input)?);
     for &(i, o) in TEST2.iter() {
-        assert_eq!(part_two(i), o);
+        assert_eq!(part_two(i)?, o);
     }
-    println!("part two: {}", part_two(&input));
+    println!("part two: {}", part_two(&input)?);
+    Ok(())
 }
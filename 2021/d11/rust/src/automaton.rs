@@ -0,0 +1,248 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutomatonError {
+    ParseError(String),
+    // a row whose length differs from the first
+    Ragged(usize),
+}
+
+impl fmt::Display for AutomatonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AutomatonError::ParseError(msg) => write!(f, "parse error: {}", msg),
+            AutomatonError::Ragged(row) => write!(f, "row {} is a different length to the first", row),
+        }
+    }
+}
+
+// which cells a flash spills energy into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    // the four orthogonal cells
+    VonNeumann,
+    // all eight surrounding cells
+    Moore,
+    // any set of (dx, dy) offsets
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Neighbourhood {
+    fn offsets(&self) -> &[(isize, isize)] {
+        match self {
+            Neighbourhood::VonNeumann => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighbourhood::Moore => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+            Neighbourhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+// "moore", "von-neumann", or offsets written as "dx,dy" separated by ';'
+impl std::str::FromStr for Neighbourhood {
+    type Err = AutomatonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "moore" => Ok(Neighbourhood::Moore),
+            "von-neumann" => Ok(Neighbourhood::VonNeumann),
+            _ => s.split(';').map(|pair| {
+                let bad = || AutomatonError::ParseError(format!("bad offset {:?}", pair));
+                let (dx, dy) = pair.split_once(',').ok_or_else(bad)?;
+                Ok((dx.trim().parse().map_err(|_| bad())?, dy.trim().parse().map_err(|_| bad())?))
+            }).collect::<Result<_, _>>().map(Neighbourhood::Custom),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepStats {
+    // 1 for the first step
+    pub step: usize,
+    pub flashes: usize,
+    // true when every cell flashed at once
+    pub synchronized: bool,
+}
+
+// every step each cell gains one energy; a cell going over the threshold flashes, giving its
+// neighbours one energy each, which can push them over in turn. a cell flashes at most once a
+// step and anything that flashed starts the next step at zero
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Automaton {
+    width: usize,
+    height: usize,
+    energy: Vec<u32>,
+    threshold: u32,
+    neighbourhood: Neighbourhood,
+    steps: usize,
+}
+
+impl Automaton {
+    pub fn new(rows: Vec<Vec<u32>>) -> Result<Self, AutomatonError> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |r| r.len());
+        if let Some(ragged) = rows.iter().position(|r| r.len() != width) {
+            return Err(AutomatonError::Ragged(ragged));
+        }
+
+        Ok(Automaton {
+            width,
+            height,
+            energy: rows.into_iter().flatten().collect(),
+            threshold: 9,
+            neighbourhood: Neighbourhood::Moore,
+            steps: 0,
+        })
+    }
+
+    // the most energy a cell can hold without flashing
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    // bumps a cell's energy, queueing it the moment it crosses the threshold so it's only ever
+    // queued once per step. energy saturates, since nothing goes over a threshold of u32::MAX
+    fn charge(&mut self, cell: usize, worklist: &mut Vec<usize>) {
+        let before = self.energy[cell];
+        self.energy[cell] = before.saturating_add(1);
+        if before == self.threshold && self.energy[cell] > self.threshold {
+            worklist.push(cell);
+        }
+    }
+
+    pub fn step(&mut self) -> StepStats {
+        let mut worklist = vec![];
+        for cell in 0..self.energy.len() {
+            self.charge(cell, &mut worklist);
+        }
+
+        // copied out so charging cells doesn't fight the borrow of the neighbourhood
+        let offsets = self.neighbourhood.offsets().to_vec();
+        let mut flashed = vec![];
+        while let Some(cell) = worklist.pop() {
+            flashed.push(cell);
+
+            let (x, y) = ((cell % self.width) as isize, (cell / self.width) as isize);
+            for &(dx, dy) in &offsets {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= self.width as isize || ny >= self.height as isize {
+                    continue;
+                }
+                self.charge(ny as usize * self.width + nx as usize, &mut worklist);
+            }
+        }
+
+        for &cell in &flashed {
+            self.energy[cell] = 0;
+        }
+
+        self.steps += 1;
+        StepStats { step: self.steps, flashes: flashed.len(), synchronized: flashed.len() == self.energy.len() }
+    }
+
+    pub fn run(&mut self, n: usize) -> Vec<StepStats> {
+        (0..n).map(|_| self.step()).collect()
+    }
+
+    // steps until a step satisfies the predicate, giving up after `limit` steps
+    pub fn run_until(&mut self, limit: usize, mut done: impl FnMut(&StepStats) -> bool) -> Option<StepStats> {
+        (0..limit).map(|_| self.step()).find(|stats| done(stats))
+    }
+}
+
+impl std::str::FromStr for Automaton {
+    type Err = AutomatonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                l.trim().chars()
+                    .map(|c| c.to_digit(10).ok_or_else(|| AutomatonError::ParseError(format!("bad energy level {:?}", c))))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        Automaton::new(rows)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Automaton {
+        include_str!("../../test.txt").parse().unwrap()
+    }
+
+    fn energy(octos: &Automaton, x: usize, y: usize) -> u32 {
+        octos.energy[y * octos.width + x]
+    }
+
+    #[test]
+    fn sample_runs() {
+        let mut octos = sample();
+        let stats = octos.run(10);
+        assert_eq!(stats.iter().map(|s| s.flashes).sum::<usize>(), 204);
+        assert_eq!(stats[1].flashes, 35);
+
+        octos.run(90);
+        assert_eq!(octos.steps, 100);
+
+        let mut octos = sample();
+        let synced = octos.run_until(1000, |s| s.synchronized).unwrap();
+        assert_eq!(synced, StepStats { step: 195, flashes: 100, synchronized: true });
+    }
+
+    #[test]
+    fn cascade() {
+        // the small example: the ring of nines flashes and sets off the middle one
+        let mut octos: Automaton = "11111\n19991\n19191\n19991\n11111\n".parse().unwrap();
+        assert_eq!(octos.step().flashes, 9);
+        assert_eq!(energy(&octos, 0, 0), 3);
+        assert_eq!(energy(&octos, 2, 2), 0);
+        assert_eq!(octos.step().flashes, 0);
+    }
+
+    #[test]
+    fn configurable() {
+        let mut octos = "000\n030\n000\n".parse::<Automaton>().unwrap()
+            .with_threshold(3)
+            .with_neighbourhood(Neighbourhood::VonNeumann);
+        octos.step();
+        assert_eq!((energy(&octos, 1, 0), energy(&octos, 0, 0), energy(&octos, 1, 1)), (2, 1, 0));
+
+        // a one-way neighbourhood only spills to the right
+        let mut octos = "33\n".parse::<Automaton>().unwrap()
+            .with_threshold(3)
+            .with_neighbourhood(Neighbourhood::Custom(vec![(1, 0)]));
+        let stats = octos.step();
+        assert_eq!(stats.flashes, 2);
+        assert!(stats.synchronized);
+        assert_eq!(octos.run_until(3, |s| s.flashes > 0), None);
+
+        // nothing can go over the largest threshold, and a full cell just stays full
+        let mut octos = "9\n".parse::<Automaton>().unwrap().with_threshold(u32::MAX);
+        octos.energy[0] = u32::MAX - 1;
+        assert_eq!(octos.run_until(3, |s| s.flashes > 0), None);
+        assert_eq!(energy(&octos, 0, 0), u32::MAX);
+    }
+
+    #[test]
+    fn neighbourhoods() {
+        assert_eq!("moore".parse(), Ok(Neighbourhood::Moore));
+        assert_eq!("von-neumann".parse(), Ok(Neighbourhood::VonNeumann));
+        assert_eq!("1,0;-2, 1".parse(), Ok(Neighbourhood::Custom(vec![(1, 0), (-2, 1)])));
+        assert!(matches!("1;0".parse::<Neighbourhood>(), Err(AutomatonError::ParseError(_))));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("12\n1\n".parse::<Automaton>(), Err(AutomatonError::Ragged(1)));
+        assert!(matches!("1a\n".parse::<Automaton>(), Err(AutomatonError::ParseError(_))));
+    }
+}
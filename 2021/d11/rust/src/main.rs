@@ -1,154 +1,21 @@
+mod automaton;
+
 use std::path::Path;
 use std::fs::File;
 use std::io::Read;
 use std::time::Instant;
 
-fn flashes(octos: &Vec<Vec<Option<usize>>>) -> usize {
-    let mut octos = octos.clone();
-
-    let mut flashes = 0;
-
-    for _ in 0..100 {
-        // increase each energy level
-        for row in octos.iter_mut() {
-            for o in row {
-                if let Some(o) = o {
-                    *o += 1;
-                } else {
-                    unreachable!("no octos have flashed yet, so they should all be collecting energy");
-                }
-            }
-        }
-
-        // while there are new flashes:
-        'flashes: loop {
-            for x in 0..octos.len() {
-                for y in 0..octos[x].len() {
-                    if let Some(octo) = octos[x][y] {
-                        // flash any above 9 that have not yet flashed
-                        if octo > 9 {
-                            octos[x][y].take();
-                            // count flashed octos and add to `flashes`
-                            flashes += 1;
+use automaton::{Automaton, AutomatonError, Neighbourhood};
 
-                            // increment their neighbors
-                            let neighbors = [
-                                (x.checked_sub(1), y.checked_sub(1)),
-                                (x.checked_sub(1), Some(y)),
-                                (x.checked_sub(1), y.checked_add(1)),
-                                (Some(x), y.checked_sub(1)),
-                                (Some(x), y.checked_add(1)),
-                                (x.checked_add(1), y.checked_sub(1)),
-                                (x.checked_add(1), Some(y)),
-                                (x.checked_add(1), y.checked_add(1)),
-                            ];
-
-                            for (nx, ny) in neighbors.into_iter().filter_map(|(nx, ny)| nx.zip(ny)) {
-                                if nx >= 10 || ny >= 10 { continue; }
-                                if let Some(ref mut o) = octos[nx][ny] {
-                                    *o += 1;
-                                }
-                            }
-
-                            continue 'flashes;
-                        }
-                    }
-                }
-            }
-
-            break;
-        }
-
-        // reset any flashed octos to Some(0)
-        for row in octos.iter_mut() {
-            for o in row {
-                if let None = o {
-                    let _ = o.insert(0);
-                }
-            }
-        }
-    }
-
-    flashes
-}
-
-fn sync(octos: &Vec<Vec<Option<usize>>>) -> usize {
-    let mut octos = octos.clone();
-
-    // safety: the input is guaranteed not to have a cycle
-    for step in 1.. {
-        // increase each energy level
-        for row in octos.iter_mut() {
-            for o in row {
-                if let Some(o) = o {
-                    *o += 1;
-                } else {
-                    unreachable!("no octos have flashed yet, so they should all be collecting energy");
-                }
-            }
-        }
+// how long part two waits for the octopuses to line up
+const SYNC_LIMIT: usize = 1_000_000;
 
-        // while there are new flashes:
-        'flashes: loop {
-            for x in 0..octos.len() {
-                for y in 0..octos[x].len() {
-                    if let Some(octo) = octos[x][y] {
-                        // flash any above 9 that have not yet flashed
-                        if octo > 9 {
-                            octos[x][y].take();
-
-                            // increment their neighbors
-                            let neighbors = [
-                                (x.checked_sub(1), y.checked_sub(1)),
-                                (x.checked_sub(1), Some(y)),
-                                (x.checked_sub(1), y.checked_add(1)),
-                                (Some(x), y.checked_sub(1)),
-                                (Some(x), y.checked_add(1)),
-                                (x.checked_add(1), y.checked_sub(1)),
-                                (x.checked_add(1), Some(y)),
-                                (x.checked_add(1), y.checked_add(1)),
-                            ];
-
-                            for (nx, ny) in neighbors.into_iter().filter_map(|(nx, ny)| nx.zip(ny)) {
-                                // ok, fine, hard-code the dimensions :(
-                                if nx >= 10 || ny >= 10 { continue; }
-                                if let Some(ref mut o) = octos[nx][ny] {
-                                    *o += 1;
-                                }
-                            }
-
-                            continue 'flashes;
-                        }
-                    }
-                }
-            }
-
-            break;
-        }
-
-        if octos.iter().all(|row| row.iter().all(|octo| octo.is_none())) {
-            return step;
-        }
-
-        // reset any flashed octos to Some(0)
-        for row in octos.iter_mut() {
-            for o in row {
-                if let None = o {
-                    let _ = o.insert(0);
-                }
-            }
-        }
-    }
-
-    unreachable!()
-}
-
-fn main() {
+fn main() -> Result<(), AutomatonError> {
     let mut args = std::env::args();
 
     let input_filename = args.nth(1).unwrap_or_else(|| {
         eprintln!("No input file specified");
-        eprintln!("Usage: ./run <input filename>");
+        eprintln!("Usage: ./run <input filename> [--threshold=N] [--neighbourhood=moore|von-neumann|dx,dy;...]");
         std::process::exit(1);
     });
 
@@ -156,15 +23,28 @@ fn main() {
     let mut input = String::new();
     input_file.read_to_string(&mut input).expect("failed to read from file");
 
-    let octos = input.lines()
-        .map(|l| l.chars().map(|c| (c.to_digit(10).unwrap() as usize).into()).collect())
-        .collect();
+    let mut octos: Automaton = input.parse()?;
+    for arg in args {
+        if let Some(raw) = arg.strip_prefix("--threshold=") {
+            let threshold = raw.parse().map_err(|_| AutomatonError::ParseError(format!("bad threshold {:?}", raw)))?;
+            octos = octos.with_threshold(threshold);
+        } else if let Some(raw) = arg.strip_prefix("--neighbourhood=") {
+            octos = octos.with_neighbourhood(raw.parse::<Neighbourhood>()?);
+        } else {
+            return Err(AutomatonError::ParseError(format!("unknown argument {:?}", arg)));
+        }
+    }
 
     let now = Instant::now();
-    println!("part 1: {}", flashes(&octos));
+    let flashes: usize = octos.clone().run(100).iter().map(|s| s.flashes).sum();
+    println!("part 1: {}", flashes);
     println!("time: {} ms", now.elapsed().as_nanos() as f64 / 1000000000f64);
 
     let now = Instant::now();
-    println!("part 2: {}", sync(&octos));
+    match octos.clone().run_until(SYNC_LIMIT, |s| s.synchronized) {
+        Some(stats) => println!("part 2: {}", stats.step),
+        None => println!("part 2: the octopuses didn't synchronize within {} steps", SYNC_LIMIT),
+    }
     println!("time: {} ms", now.elapsed().as_nanos() as f64 / 1000000000f64);
+    Ok(())
 }
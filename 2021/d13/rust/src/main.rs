@@ -1,10 +1,13 @@
-#![feature(drain_filter)]
-
+use std::collections::HashSet;
+use std::error::Error;
 use std::path::Path;
 use std::fs::File;
 use std::io::Read;
 use std::time::Instant;
 
+use render::{Canvas, Render};
+use sections::Sections;
+
 enum Direction {
     Up,
     Left,
@@ -37,31 +40,46 @@ impl From<&str> for Direction {
     }
 }
 
+struct Paper<'a>(&'a HashSet<(isize, isize)>);
+
+impl<'a> Render for Paper<'a> {
+    fn render(&self, canvas: &mut Canvas) {
+        for &(x, y) in self.0 {
+            canvas.put(x as usize, y as usize, '#');
+        }
+    }
+}
+
 fn fold(points: &[(isize, isize)], folds: &[(Direction, isize)], print: bool) -> usize {
-    let mut points: std::collections::HashSet<_> = points.iter().map(|i| i.clone()).collect();
+    let mut points: HashSet<_> = points.iter().map(|i| i.clone()).collect();
 
     for (dir, loc) in folds {
         points = points.iter().map(|p| dir.apply(p, *loc)).collect();
     }
 
     if print {
-        // unwrap: assuming input data contains at least one entry
-        for y in 0..=points.iter().max_by_key(|(_, y)| y).unwrap().1 {
-            for x in 0..=points.iter().max_by_key(|(x, _)| x).unwrap().0 {
-                if points.contains( &(x, y) ) {
-                    print!("#");
-                } else {
-                    print!(" ");
-                }
-            }
-            println!();
-        }
+        print!("{}", Paper(&points).text());
     }
 
     points.len()
 }
 
-fn main() {
+fn parse_point(line: &str) -> Result<(isize, isize), String> {
+    let (x, y) = line.trim().split_once(',').ok_or_else(|| format!("expected x,y but got {:?}", line))?;
+    let coord = |c: &str| c.parse::<isize>().map_err(|e| format!("bad coordinate {:?}: {}", c, e));
+    Ok((coord(x)?, coord(y)?))
+}
+
+fn parse_fold(line: &str) -> Result<(Direction, isize), String> {
+    let word = line.trim().strip_prefix("fold along ").ok_or_else(|| format!("expected a fold but got {:?}", line))?;
+    let (dir, loc) = match word.split_once('=') {
+        Some((dir @ ("x" | "y"), loc)) => (dir, loc),
+        _ => return Err(format!("bad fold equation {:?}", word)),
+    };
+    Ok((dir.into(), loc.parse().map_err(|e| format!("bad fold location {:?}: {}", loc, e))?))
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
     let mut args = std::env::args();
 
     let input_filename = args.nth(1).unwrap_or_else(|| {
@@ -74,31 +92,9 @@ fn main() {
     let mut input = String::new();
     input_file.read_to_string(&mut input).expect("failed to read from file");
 
-    let lines: Vec<&str> = input.lines().map(|l| l.trim()).collect();
-
-    let spot = lines.binary_search_by(|x| {
-        if x.contains(',') {
-            std::cmp::Ordering::Less
-        } else if x.contains('=') {
-            std::cmp::Ordering::Greater
-        } else {
-            std::cmp::Ordering::Equal
-        }
-    }).expect("couldn't find the blank line");
-    let (points, folds) = lines.split_at(spot);
-
-    let points = points.iter().map(|l| {
-        let mut coords = l.split(',')
-            .map(|i| i.parse::<isize>().expect("failed to parse coord number"));
-        (coords.next().expect("failed to get first coord"), coords.next().expect("failed to get second coord"))
-    }).collect::<Vec<_>>();
-
-    let folds = folds[1..].iter().map(|l| {
-        let word = l.split_whitespace().nth(2).expect("failed to get fold equation");
-        let mut parts = word.split('=');
-
-        (parts.next().expect("failed to get direction").into(), parts.next().expect("failed to get fold location").parse::<isize>().expect("failed to parse fold location"))
-    }).collect::<Vec<_>>();
+    let sections = Sections::new(&input).named(&["points", "folds"])?;
+    let points = sections.get("points")?.parse_lines(parse_point)?;
+    let folds = sections.get("folds")?.parse_lines(parse_fold)?;
 
     let now = Instant::now();
     println!("part 1: {}", fold(&points, &folds[0..1], false));
@@ -107,4 +103,5 @@ fn main() {
     let now = Instant::now();
     println!("part 2: {}", fold(&points, &folds, true));
     println!("time: {} ms", now.elapsed().as_nanos() as f64 / 1000000000f64);
+    Ok(())
 }
@@ -5,6 +5,11 @@ use std::time::Instant;
 
 use std::collections::HashMap;
 
+mod polymer;
+
+use polymer::Engine;
+
+#[cfg(test)]
 fn polymerize_alloc(init: &Vec<char>, insertions: &HashMap<(char, char), char>, steps: usize) -> usize {
     let mut polymer = init.clone();
 
@@ -45,6 +50,7 @@ fn polymerize_alloc(init: &Vec<char>, insertions: &HashMap<(char, char), char>,
     max - min
 }
 
+#[cfg(test)]
 fn do_recurse(a: char, b: char, insertions: &HashMap<(char, char), char>, steps:  usize, appearances: &mut HashMap<char, usize>) {
     // unwrap: assuming that all possible insertion combinations are covered
     let c = *insertions.get( &(a, b) ).unwrap();
@@ -58,6 +64,7 @@ fn do_recurse(a: char, b: char, insertions: &HashMap<(char, char), char>, steps:
     }
 }
 
+#[cfg(test)]
 fn polymerize_recurse(init: &Vec<char>, insertions: &HashMap<(char, char), char>, steps: usize) -> usize {
     let mut appearances = HashMap::new();
 
@@ -78,6 +85,7 @@ fn polymerize_recurse(init: &Vec<char>, insertions: &HashMap<(char, char), char>
     max - min
 }
 
+#[cfg(test)]
 fn polymerize_pair_count(init: &Vec<char>, insertions: &HashMap<(char, char), char>, steps: usize) -> usize {
     let mut pair_counts = HashMap::new();
 
@@ -117,6 +125,21 @@ fn polymerize_pair_count(init: &Vec<char>, insertions: &HashMap<(char, char), ch
     max - min
 }
 
+fn parse(input: &str) -> (Vec<char>, HashMap<(char, char), char>) {
+    let mut lines: Vec<&str> = input.lines().map(|l| l.trim()).collect();
+
+    let init = lines.drain(0..2).nth(0).expect("failed to read initial polymer").chars().collect();
+
+    let insertions = lines.into_iter().map(|l| {
+        let mut parts = l.split(" -> ");
+        let mut from = parts.next().expect("failed to find 'from' side of reaction").chars();
+        let to = parts.next().expect("failed to find 'to' side of reaction").chars().next().expect("'to' side of reaction was empty");
+        ((from.next().expect("failed to get start of 'from'"), from.next().expect("failed to get end of 'from'")), to)
+    }).collect();
+
+    (init, insertions)
+}
+
 fn main() {
     let mut args = std::env::args();
 
@@ -130,22 +153,18 @@ fn main() {
     let mut input = String::new();
     input_file.read_to_string(&mut input).expect("failed to read from file");
 
-    let mut lines: Vec<&str> = input.lines().map(|l| l.trim()).collect();
+    let (init, insertions) = parse(&input);
 
-    let init = lines.drain(0..2).nth(0).expect("failed to read initial polymer").chars().collect();
-
-    let insertions = lines.into_iter().map(|l| {
-        let mut parts = l.split(" -> ");
-        let mut from = parts.next().expect("failed to find 'from' side of reaction").chars();
-        let to = parts.next().expect("failed to find 'to' side of reaction").chars().next().expect("'to' side of reaction was empty");
-        ((from.next().expect("failed to get start of 'from'"), from.next().expect("failed to get end of 'from'")), to)
-    }).collect();
+    let engine = Engine::new(&init, &insertions).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
 
     let now = Instant::now();
-    println!("part 1: {}", polymerize_pair_count(&init, &insertions, 10));
+    println!("part 1: {}", engine.spread::<u64>(10));
     println!("time: {} ms", now.elapsed().as_nanos() as f64 / 1000000000f64);
 
     let now = Instant::now();
-    println!("part 2: {}", polymerize_pair_count(&init, &insertions, 40));
+    println!("part 2: {}", engine.spread::<u64>(40));
     println!("time: {} ms", now.elapsed().as_nanos() as f64 / 1000000000f64);
 }
@@ -0,0 +1,246 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use numeric::{Count, Matrix};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolymerError {
+    EmptyTemplate,
+    // a pair the polymer will grow into has no insertion rule
+    MissingRule(char, char),
+}
+
+impl fmt::Display for PolymerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolymerError::EmptyTemplate => write!(f, "the polymer template is empty"),
+            PolymerError::MissingRule(a, b) => write!(f, "no insertion rule for the pair {}{}", a, b),
+        }
+    }
+}
+
+// Each step rewrites every pair AB with rule AB -> C into the pairs AC and CB, which is linear
+// in the pair counts. The transition matrix is built once and raised to the step count by
+// repeated squaring.
+pub struct Engine {
+    template: Vec<char>,
+    pairs: Vec<(char, char)>,
+    // where each pair sits in `pairs`
+    index: HashMap<(char, char), usize>,
+    // indices into `pairs`: each pair and the two pairs it becomes
+    transitions: Vec<(usize, usize)>,
+}
+
+impl Engine {
+    pub fn new(template: &[char], insertions: &HashMap<(char, char), char>) -> Result<Self, PolymerError> {
+        if template.is_empty() {
+            return Err(PolymerError::EmptyTemplate);
+        }
+
+        // only the pairs reachable from the template need a rule
+        let mut index = HashMap::new();
+        let mut pairs = vec![];
+        let mut queue: Vec<(char, char)> = template.windows(2).map(|w| (w[0], w[1])).collect();
+        let mut seen: HashSet<(char, char)> = queue.iter().copied().collect();
+
+        while let Some((a, b)) = queue.pop() {
+            index.insert((a, b), pairs.len());
+            pairs.push((a, b));
+
+            let c = *insertions.get(&(a, b)).ok_or(PolymerError::MissingRule(a, b))?;
+            for next in [(a, c), (c, b)] {
+                if seen.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+
+        let transitions = pairs.iter()
+            .map(|&(a, b)| {
+                let c = insertions[&(a, b)];
+                (index[&(a, c)], index[&(c, b)])
+            })
+            .collect();
+
+        Ok(Engine { template: template.to_vec(), pairs, index, transitions })
+    }
+
+    fn matrix<T: Count>(&self) -> Matrix<T> {
+        let mut m: Matrix<T> = Matrix::zeros(self.pairs.len());
+
+        for (from, &(left, right)) in self.transitions.iter().enumerate() {
+            for to in [left, right] {
+                let cur = m.get(to, from).plus(&T::one());
+                m.set(to, from, cur);
+            }
+        }
+
+        m
+    }
+
+    pub fn pair_counts<T: Count>(&self, steps: u64) -> Vec<((char, char), T)> {
+        let mut start = vec![T::zero(); self.pairs.len()];
+        for w in self.template.windows(2) {
+            // every template pair is indexed in `new`
+            let idx = self.index[&(w[0], w[1])];
+            start[idx] = start[idx].plus(&T::one());
+        }
+
+        let counts = self.matrix::<T>().pow(steps).apply(&start);
+        self.pairs.iter().copied().zip(counts).collect()
+    }
+
+    // how many of each element the polymer holds after `steps`, sorted by element
+    pub fn element_counts<T: Count>(&self, steps: u64) -> Vec<(char, T)> {
+        let mut counts: HashMap<char, T> = HashMap::new();
+
+        // every element starts exactly one pair, except the last, which never moves
+        for ((a, _), count) in self.pair_counts::<T>(steps) {
+            let e = counts.entry(a).or_insert_with(T::zero);
+            *e = e.plus(&count);
+        }
+        // unwrap: the template was checked to be non-empty in `new`
+        let e = counts.entry(*self.template.last().unwrap()).or_insert_with(T::zero);
+        *e = e.plus(&T::one());
+
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by_key(|(c, _)| *c);
+        counts
+    }
+
+    // the most common element's count less the least common's. the two elements are picked by
+    // rough magnitude, so `T` only has to count: a modular `T` gives the spread under its modulus
+    pub fn spread<T: Count>(&self, steps: u64) -> T {
+        let rough = self.element_counts::<Magnitude>(steps);
+        // unwrap: the template is non-empty, so there is at least one element
+        let most = rough.iter().max_by(|a, b| a.1.compare(&b.1)).unwrap().0;
+        let least = rough.iter().min_by(|a, b| a.1.compare(&b.1)).unwrap().0;
+
+        let counts: HashMap<char, T> = self.element_counts::<T>(steps).into_iter().collect();
+        counts[&most].minus(&counts[&least])
+    }
+}
+
+// an approximate count that never overflows, kept as a mantissa in [1, 2) and a binary exponent;
+// only good for ranking, and counts that agree to within float precision may rank either way
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Magnitude {
+    mantissa: f64,
+    exp: i64,
+}
+
+impl Magnitude {
+    fn new(mantissa: f64, exp: i64) -> Self {
+        if mantissa == 0.0 {
+            return Magnitude { mantissa: 0.0, exp: 0 };
+        }
+
+        let shift = mantissa.log2().floor();
+        Magnitude { mantissa: mantissa / shift.exp2(), exp: exp + shift as i64 }
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        let key = |m: &Self| (m.mantissa != 0.0, m.exp);
+        key(self).cmp(&key(other)).then(self.mantissa.total_cmp(&other.mantissa))
+    }
+}
+
+impl Count for Magnitude {
+    fn from_u64(n: u64) -> Self {
+        Magnitude::new(n as f64, 0)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        let (hi, lo) = if self.compare(other) == Ordering::Less { (other, self) } else { (self, other) };
+        if lo.mantissa == 0.0 || hi.exp - lo.exp > 64 {
+            return *hi;
+        }
+
+        Magnitude::new(hi.mantissa + lo.mantissa / ((hi.exp - lo.exp) as f64).exp2(), hi.exp)
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        Magnitude::new(self.mantissa * other.mantissa, self.exp + other.exp)
+    }
+
+    // as rough as the counts themselves, and clamped at zero
+    fn minus(&self, other: &Self) -> Self {
+        Magnitude::new((self.mantissa - other.mantissa / ((self.exp - other.exp) as f64).exp2()).max(0.0), self.exp)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use numeric::{BigUint, Mod};
+
+    fn sample() -> (Vec<char>, HashMap<(char, char), char>) {
+        crate::parse(include_str!("../../test.txt"))
+    }
+
+    #[test]
+    fn sample_spread() {
+        let (template, insertions) = sample();
+        let engine = Engine::new(&template, &insertions).unwrap();
+
+        assert_eq!(engine.spread::<u64>(10), 1588);
+        assert_eq!(engine.spread::<u64>(40), 2188189693529);
+        assert_eq!(engine.spread::<BigUint>(40), BigUint::from(2188189693529u64));
+        assert_eq!(engine.spread::<Mod<1_000_000_007>>(40), Mod(2188189693529 % 1_000_000_007));
+    }
+
+    #[test]
+    fn modular_spread() {
+        let (template, insertions) = sample();
+        let engine = Engine::new(&template, &insertions).unwrap();
+
+        let exact = engine.spread::<BigUint>(300) % 1_000_000_007u64;
+        assert_eq!(engine.spread::<Mod<1_000_000_007>>(300), Mod(u64::try_from(exact).unwrap()));
+    }
+
+    #[test]
+    fn matches_stepwise_strategies() {
+        let (template, insertions) = sample();
+        let engine = Engine::new(&template, &insertions).unwrap();
+
+        for steps in [1, 2, 5, 10] {
+            let expected = engine.spread::<u64>(steps) as usize;
+            assert_eq!(expected, crate::polymerize_alloc(&template, &insertions, steps as usize));
+            assert_eq!(expected, crate::polymerize_recurse(&template, &insertions, steps as usize));
+            assert_eq!(expected, crate::polymerize_pair_count(&template, &insertions, steps as usize));
+        }
+    }
+
+    #[test]
+    fn huge_step_counts() {
+        let (template, insertions) = sample();
+        let engine = Engine::new(&template, &insertions).unwrap();
+
+        // after n steps the polymer has (len - 1) * 2^n + 1 elements
+        let total = engine.element_counts::<Mod<1_000_000_007>>(1_000_000_000_000)
+            .into_iter()
+            .fold(Mod(0), |acc, (_, c)| acc.plus(&c));
+        let mut expected = Mod::<1_000_000_007>(1);
+        let mut base = Mod::<1_000_000_007>(2);
+        let mut exp = 1_000_000_000_000u64;
+        while exp > 0 {
+            if exp & 1 == 1 { expected = expected.times(&base); }
+            base = base.times(&base);
+            exp >>= 1;
+        }
+        assert_eq!(total, expected.times(&Mod(3)).plus(&Mod(1)));
+
+        let big = engine.element_counts::<BigUint>(200);
+        assert!(big.iter().all(|(_, c)| c.bits() > 64));
+    }
+
+    #[test]
+    fn missing_rule() {
+        let (template, mut insertions) = sample();
+        insertions.remove(&('C', 'H'));
+
+        assert_eq!(Engine::new(&template, &insertions).err(), Some(PolymerError::MissingRule('C', 'H')));
+        assert_eq!(Engine::new(&[], &insertions).err(), Some(PolymerError::EmptyTemplate));
+    }
+}
@@ -1,52 +1,18 @@
+mod navigation;
+
 use std::path::Path;
 use std::fs::File;
 use std::io::Read;
 
-fn locate1(lines: &Vec<&str>) -> (usize, usize) {
-    let mut position = 0;
-    let mut depth = 0;
-
-    for line in lines {
-        let mut split = line.split_ascii_whitespace();
-        let direction = split.next().expect("no direction");
-        let amount: usize = split.next().expect("no amount").parse().expect("amount couldn't be parsed");
-
-        match direction {
-            "forward" => position += amount,
-            "down" => depth += amount,
-            "up" => depth -= amount,
-            _ => panic!("unknown direction: {}", direction)
-        }
-    }
-
-    (position, depth)
-}
-
-fn locate2(lines: &Vec<&str>) -> (usize, usize) {
-    let mut position = 0;
-    let mut depth = 0;
-    let mut aim = 0;
-
-    for line in lines {
-        let mut split = line.split_ascii_whitespace();
-        let direction = split.next().expect("no direction");
-        let amount: usize = split.next().expect("no amount").parse().expect("amount couldn't be parsed");
-
-        match direction {
-            "forward" => {
-                position += amount;
-                depth += amount * aim;
-            },
-            "down" => aim += amount,
-            "up" => aim -= amount,
-            _ => panic!("unknown direction: {}", direction)
-        }
-    }
+use navigation::{Aimed, CommandError, Navigator, Plain};
 
-    (position, depth)
+fn locate(navigator: impl Navigator, course: &[navigation::SubCommand]) -> (i64, i64) {
+    // unwrap: a trajectory always has at least the starting position
+    let end = navigation::replay(navigator, course).pop().unwrap();
+    (end.x, end.depth)
 }
 
-fn main() {
+fn main() -> Result<(), CommandError> {
     let mut args = std::env::args();
 
     let input_filename = args.nth(1).unwrap_or_else(|| {
@@ -59,14 +25,14 @@ fn main() {
     let mut input = String::new();
     input_file.read_to_string(&mut input).expect("failed to read from file");
 
-    let lines = input.lines()
-        .collect();
+    let course = navigation::parse_course(&input)?;
 
-    let (position, depth) = locate1(&lines);
+    let (position, depth) = locate(Plain::default(), &course);
 
     println!("part 1: position: {}, depth: {} -- multiplied: {}", position, depth, position * depth);
 
-    let (position, depth) = locate2(&lines);
+    let (position, depth) = locate(Aimed::default(), &course);
 
     println!("part 2: position: {}, depth: {} -- multiplied: {}", position, depth, position * depth);
+    Ok(())
 }
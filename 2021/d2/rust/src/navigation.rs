@@ -0,0 +1,186 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    // the 1-based line of the course it was on, and what was wrong
+    ParseError(usize, String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::ParseError(line, msg) => write!(f, "line {}: {}", line, msg),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubCommand {
+    Forward(i64),
+    Down(i64),
+    Up(i64),
+}
+
+impl std::str::FromStr for SubCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (direction, amount) = s.trim().split_once(' ').ok_or_else(|| format!("expected a direction and an amount, got {:?}", s))?;
+        let amount = amount.trim().parse().map_err(|e| format!("bad amount {:?}: {}", amount, e))?;
+
+        match direction {
+            "forward" => Ok(SubCommand::Forward(amount)),
+            "down" => Ok(SubCommand::Down(amount)),
+            "up" => Ok(SubCommand::Up(amount)),
+            _ => Err(format!("unknown direction: {}", direction)),
+        }
+    }
+}
+
+pub fn parse_course(input: &str) -> Result<Vec<SubCommand>, CommandError> {
+    input.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| l.parse().map_err(|e| CommandError::ParseError(i + 1, e)))
+        .collect()
+}
+
+// where the submarine is; depth grows downwards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
+    pub depth: i64,
+}
+
+// a model of what the commands mean. a navigator carries whatever state it needs on top of
+// the position, so new models only have to say how each command changes that state
+pub trait Navigator {
+    fn apply(&mut self, command: SubCommand);
+    fn position(&self) -> Position;
+}
+
+// forward moves ahead, down and up change depth
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Plain {
+    position: Position,
+}
+
+impl Navigator for Plain {
+    fn apply(&mut self, command: SubCommand) {
+        match command {
+            SubCommand::Forward(n) => self.position.x += n,
+            SubCommand::Down(n) => self.position.depth += n,
+            SubCommand::Up(n) => self.position.depth -= n,
+        }
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+// down and up tilt the submarine, and forward moves along the tilt
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Aimed {
+    position: Position,
+    aim: i64,
+}
+
+impl Navigator for Aimed {
+    fn apply(&mut self, command: SubCommand) {
+        match command {
+            SubCommand::Forward(n) => {
+                self.position.x += n;
+                self.position.depth += n * self.aim;
+            }
+            SubCommand::Down(n) => self.aim += n,
+            SubCommand::Up(n) => self.aim -= n,
+        }
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+// runs the course, returning every position along the way with the starting one first
+pub fn replay(mut navigator: impl Navigator, course: &[SubCommand]) -> Vec<Position> {
+    let mut trajectory = vec![navigator.position()];
+    for &command in course {
+        navigator.apply(command);
+        trajectory.push(navigator.position());
+    }
+    trajectory
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Vec<SubCommand> {
+        parse_course(include_str!("../../test.txt")).unwrap()
+    }
+
+    fn end(trajectory: &[Position]) -> (i64, i64) {
+        let last = trajectory.last().unwrap();
+        (last.x, last.depth)
+    }
+
+    #[test]
+    fn sample_course() {
+        let course = sample();
+        assert_eq!(course[0], SubCommand::Forward(5));
+
+        let plain = replay(Plain::default(), &course);
+        assert_eq!(plain.len(), course.len() + 1);
+        assert_eq!(plain[0], Position::default());
+        assert_eq!(end(&plain), (15, 10));
+
+        let aimed = replay(Aimed::default(), &course);
+        assert_eq!(aimed[2], Position { x: 5, y: 0, depth: 0 });
+        assert_eq!(aimed[3], Position { x: 13, y: 0, depth: 40 });
+        assert_eq!(end(&aimed), (15, 60));
+    }
+
+    // a model with a compass heading, where down and up turn the submarine a quarter turn
+    // right or left per unit rather than changing depth
+    #[derive(Default)]
+    struct Compass {
+        position: Position,
+        heading: i64,
+    }
+
+    impl Navigator for Compass {
+        fn apply(&mut self, command: SubCommand) {
+            match command {
+                SubCommand::Forward(n) => match self.heading.rem_euclid(4) {
+                    0 => self.position.x += n,
+                    1 => self.position.y += n,
+                    2 => self.position.x -= n,
+                    _ => self.position.y -= n,
+                },
+                SubCommand::Down(n) => self.heading += n,
+                SubCommand::Up(n) => self.heading -= n,
+            }
+        }
+
+        fn position(&self) -> Position {
+            self.position
+        }
+    }
+
+    #[test]
+    fn custom_navigator() {
+        let course = parse_course("forward 3\ndown 1\nforward 2\nup 3\nforward 1\n").unwrap();
+        let trajectory = replay(Compass::default(), &course);
+        assert_eq!(trajectory.last(), Some(&Position { x: 2, y: 2, depth: 0 }));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_course("forward 1\n\nsideways 2\n"), Err(CommandError::ParseError(3, "unknown direction: sideways".to_string())));
+        assert!(matches!(parse_course("up x\n"), Err(CommandError::ParseError(1, _))));
+        assert!(matches!(parse_course("up\n"), Err(CommandError::ParseError(1, _))));
+    }
+}
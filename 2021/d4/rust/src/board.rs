@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BingoError {
+    ParseError(String),
+    // a board with no cells
+    Empty,
+    // a board whose rows aren't all the same length
+    Ragged(usize),
+}
+
+impl fmt::Display for BingoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BingoError::ParseError(msg) => write!(f, "parse error: {}", msg),
+            BingoError::Empty => write!(f, "board has no cells"),
+            BingoError::Ragged(row) => write!(f, "row {} is a different length to the first", row),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BingoBoard {
+    width: usize,
+    height: usize,
+    marked: Vec<bool>,
+    // every cell each number appears in
+    index: HashMap<usize, Vec<usize>>,
+    // how many cells are marked in each row, column and diagonal
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    diagonals: Option<[usize; 2]>,
+    unmarked: usize,
+    won: bool,
+}
+
+impl BingoBoard {
+    pub fn new(rows: Vec<Vec<usize>>) -> Result<Self, BingoError> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |r| r.len());
+        if width == 0 {
+            return Err(BingoError::Empty);
+        }
+        if let Some(ragged) = rows.iter().position(|r| r.len() != width) {
+            return Err(BingoError::Ragged(ragged));
+        }
+
+        let numbers: Vec<usize> = rows.into_iter().flatten().collect();
+        let mut index: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (cell, &n) in numbers.iter().enumerate() {
+            index.entry(n).or_default().push(cell);
+        }
+
+        Ok(BingoBoard {
+            width,
+            height,
+            marked: vec![false; numbers.len()],
+            unmarked: numbers.iter().sum(),
+            index,
+            rows: vec![0; height],
+            cols: vec![0; width],
+            diagonals: None,
+            won: false,
+        })
+    }
+
+    // lets a full diagonal win too; only square boards have diagonals
+    pub fn with_diagonals(mut self, diagonals: bool) -> Self {
+        self.diagonals = if diagonals && self.width == self.height { Some([0, 0]) } else { None };
+        self
+    }
+
+    pub fn has_won(&self) -> bool {
+        self.won
+    }
+
+    pub fn unmarked_sum(&self) -> usize {
+        self.unmarked
+    }
+
+    // marks every cell holding `n`, returning true if that completes the board's first line
+    pub fn mark(&mut self, n: usize) -> bool {
+        let cells = match self.index.get(&n) {
+            Some(cells) => cells,
+            None => return false,
+        };
+
+        let mut line = false;
+        for &cell in cells {
+            if self.marked[cell] {
+                continue;
+            }
+            self.marked[cell] = true;
+            self.unmarked -= n;
+
+            let (x, y) = (cell % self.width, cell / self.width);
+            self.rows[y] += 1;
+            self.cols[x] += 1;
+            line |= self.rows[y] == self.width || self.cols[x] == self.height;
+
+            if let Some(diagonals) = self.diagonals.as_mut() {
+                if x == y {
+                    diagonals[0] += 1;
+                }
+                if x + y + 1 == self.width {
+                    diagonals[1] += 1;
+                }
+                line |= diagonals.contains(&self.width);
+            }
+        }
+
+        let first = line && !self.won;
+        self.won |= line;
+        first
+    }
+}
+
+impl std::str::FromStr for BingoBoard {
+    type Err = BingoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                l.split_ascii_whitespace()
+                    .map(|n| n.parse().map_err(|_| BingoError::ParseError(format!("bad board number {:?}", n))))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        BingoBoard::new(rows)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Win {
+    pub board: usize,
+    // how many numbers had been called, this one included
+    pub turn: usize,
+    pub call: usize,
+    pub score: usize,
+}
+
+// plays every call on every board, returning the boards in the order they won; boards
+// winning on the same call are ordered by their position
+pub fn play(calls: &[usize], boards: &mut [BingoBoard]) -> Vec<Win> {
+    let mut holding: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (idx, board) in boards.iter().enumerate() {
+        for &n in board.index.keys() {
+            holding.entry(n).or_default().push(idx);
+        }
+    }
+    for list in holding.values_mut() {
+        list.sort_unstable();
+    }
+
+    let mut wins = vec![];
+    let mut playing = boards.iter().filter(|b| !b.has_won()).count();
+
+    for (turn, &call) in calls.iter().enumerate() {
+        if playing == 0 {
+            break;
+        }
+
+        for &idx in holding.get(&call).into_iter().flatten() {
+            let board = &mut boards[idx];
+            if board.mark(call) {
+                playing -= 1;
+                wins.push(Win { board: idx, turn: turn + 1, call, score: board.unmarked_sum() * call });
+            }
+        }
+    }
+
+    wins
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> (Vec<usize>, Vec<BingoBoard>) {
+        // unwrap: the sample is well formed
+        crate::parse(include_str!("../../test.txt")).unwrap()
+    }
+
+    #[test]
+    fn win_order() {
+        let (calls, mut boards) = sample();
+        let wins = play(&calls, &mut boards);
+
+        assert_eq!(wins.iter().map(|w| w.board).collect::<Vec<_>>(), [2, 0, 1]);
+        assert_eq!(wins[0], Win { board: 2, turn: 12, call: 24, score: 4512 });
+        assert_eq!(wins[2].score, 1924);
+    }
+
+    #[test]
+    fn rectangular() {
+        let mut board: BingoBoard = "1 2 3\n4 5 6\n".parse().unwrap();
+        assert!(!board.mark(1));
+        assert!(board.mark(4));
+        assert!(!board.mark(2));
+        assert_eq!(board.unmarked_sum(), 3 + 5 + 6);
+
+        let mut board: BingoBoard = "1 2 3\n4 5 6\n".parse().unwrap();
+        board.mark(4);
+        board.mark(5);
+        assert!(board.mark(6));
+    }
+
+    #[test]
+    fn diagonals() {
+        let rows = "1 2 3\n4 5 6\n7 8 9\n";
+        let mut plain: BingoBoard = rows.parse().unwrap();
+        let mut diag = rows.parse::<BingoBoard>().unwrap().with_diagonals(true);
+
+        let wins: Vec<bool> = [3, 5, 7].iter().map(|&n| diag.mark(n)).collect();
+        assert_eq!(wins, [false, false, true]);
+        assert!(![3, 5, 7].iter().any(|&n| plain.mark(n)));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("1 2\n3\n".parse::<BingoBoard>().unwrap_err(), BingoError::Ragged(1));
+        assert_eq!("".parse::<BingoBoard>().unwrap_err(), BingoError::Empty);
+        assert!(matches!("1 x\n".parse::<BingoBoard>(), Err(BingoError::ParseError(_))));
+    }
+}
@@ -1,104 +1,43 @@
+mod board;
+
 use std::path::Path;
 use std::fs::File;
 use std::io::Read;
-use itertools::Itertools;
-
-fn calculate_score(board: &Vec<Vec<(usize, bool)>>) -> usize {
-    let mut score = 0;
-
-    for row in board {
-        for (num, marked) in row {
-            if !marked {
-                score += num;
-            }
-        }
-    }
-
-    score
-}
-
-fn winning_board(board: &Vec<Vec<(usize, bool)>>) -> bool {
-    let mut cols = vec![true; 5];
-
-    for row in board {
-        if row.iter().all(|(_, marked)| *marked) {
-            return true;
-        }
-
-        for (ref mut col, (_, marked)) in cols.iter_mut().zip(row.iter()) {
-            **col &= marked
-        }
-    }
-
-    if cols.iter().any(|c| *c) {
-        true
-    } else {
-        false
-    }
-}
 
-fn bingo(calls: &Vec<usize>, boards: &Vec<Vec<Vec<(usize, bool)>>>) -> usize {
-    let mut boards = boards.clone();
+use board::{BingoBoard, BingoError};
 
-    for call in calls {
-        // mark on all the boards
-        for board in boards.iter_mut() {
-            for row in board {
-                for (num, ref mut marked) in row {
-                    if num == call {
-                        *marked = true
-                    }
-                }
-            }
-        }
-
-        // check if any boards have won
-        for board in boards.iter() {
-            if winning_board(&board) {
-                return calculate_score(&board) * call;
-            }
-        }
-    }
-
-    panic!("no winning boards");
-}
-
-fn lose_bingo(calls: &Vec<usize>, boards: &Vec<Vec<Vec<(usize, bool)>>>) -> usize {
-    let mut boards = boards.clone();
-
-    for call in calls {
-        // mark on all the boards
-        for board in boards.iter_mut() {
-            for row in board {
-                for (num, ref mut marked) in row {
-                    if num == call {
-                        *marked = true
-                    }
-                }
-            }
-        }
+// the called numbers on the first line, then boards separated by blank lines
+fn parse(input: &str) -> Result<(Vec<usize>, Vec<BingoBoard>), BingoError> {
+    let mut lines = input.lines();
 
-        if boards.len() == 1 {
-            if winning_board(&boards[0]) {
-                return calculate_score(&boards[0]) * call;
+    let calls = lines.next().ok_or_else(|| BingoError::ParseError("no line of calls".to_string()))?
+        .split(',')
+        .map(|c| c.trim().parse().map_err(|_| BingoError::ParseError(format!("bad call {:?}", c))))
+        .collect::<Result<_, _>>()?;
+
+    let mut boards = vec![];
+    let mut current = String::new();
+    for line in lines.chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                boards.push(current.parse()?);
+                current.clear();
             }
         } else {
-            // check if any boards have won
-            boards = boards.into_iter().filter(|board| {
-                !winning_board(&board)
-            }).collect();
+            current.push_str(line);
+            current.push('\n');
         }
     }
 
-    panic!("no winning boards");
+    Ok((calls, boards))
 }
 
-fn main() {
+fn main() -> Result<(), BingoError> {
     let mut args = std::env::args();
 
     let input_filename = args.nth(1).unwrap_or_else(|| {
         eprintln!("No input file specified");
-        eprintln!("Usage: ./run <input filename>");
+        eprintln!("Usage: ./run <input filename> [--diagonals]");
         std::process::exit(1);
     });
 
@@ -106,19 +45,18 @@ fn main() {
     let mut input = String::new();
     input_file.read_to_string(&mut input).expect("failed to read from file");
 
-    let mut lines = input.lines();
-
-    let calls = lines.next().expect("no line of calls")
-        .split(',').map(|c| c.parse().expect("failed to parse a number"))
-        .collect();
-    let _ = lines.next().expect("no blank line");
+    let diagonals = args.any(|a| a == "--diagonals");
+    let (calls, boards) = parse(&input)?;
+    let mut boards: Vec<_> = boards.into_iter().map(|b| b.with_diagonals(diagonals)).collect();
 
-    let boards: &Vec<Vec<Vec<(usize, bool)>>> = &lines.chunks(6).into_iter().map(|board| {
-        board.take(5)
-            .map(|l| l.split_ascii_whitespace().map(|n| (n.parse().expect("failed to parse board number"), false)).collect())
-            .collect()
-    }).collect();
+    let wins = board::play(&calls, &mut boards);
 
-    println!("part 1: {}", bingo(&calls, boards));
-    println!("part 2: {}", lose_bingo(&calls, boards));
+    match (wins.first(), wins.last()) {
+        (Some(first), Some(last)) => {
+            println!("part 1: {}", first.score);
+            println!("part 2: {}", last.score);
+        }
+        _ => println!("no board won after {} calls", calls.len()),
+    }
+    Ok(())
 }
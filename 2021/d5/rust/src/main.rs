@@ -2,8 +2,13 @@ use std::path::Path;
 use std::fs::File;
 use std::io::Read;
 
+use std::collections::HashMap;
 use std::str::FromStr;
 
+use render::{Canvas, Render};
+
+mod sweep;
+
 #[derive(Debug)]
 struct Point {
     x: usize,
@@ -44,35 +49,107 @@ impl LineSegment {
         self.start.y == self.end.y
     }
 
-    // TODO is there a way to do this while returning 'impl Iterator'?
-    fn points(&self) -> Vec<(usize, usize)> {
-        if self.vertical() {
-            if self.start.x < self.end.x {
-                (self.start.x ..= self.end.x).map(|x| (x, self.start.y)).collect()
-            } else {
-                (self.end.x ..= self.start.x).map(|x| (x, self.start.y)).collect()
-            }
-        } else if self.horizontal() {
-            if self.start.y < self.end.y {
-                (self.start.y ..= self.end.y).map(|y| (self.start.x, y)).collect()
-            } else {
-                (self.end.y ..= self.start.y).map(|y| (self.start.x, y)).collect()
-            }
+    fn deltas(&self) -> (isize, isize, isize, isize) {
+        let dx = self.end.x as isize - self.start.x as isize;
+        let dy = self.end.y as isize - self.start.y as isize;
+        (dx.abs(), dy.abs(), dx.signum(), dy.signum())
+    }
+
+    fn points(&self) -> Points {
+        let (dx, dy, sx, sy) = self.deltas();
+        let (major, minor) = (dx.max(dy), dx.min(dy));
+
+        Points {
+            x: self.start.x as isize,
+            y: self.start.y as isize,
+            sx, sy,
+            x_major: dx >= dy,
+            major, minor,
+            err: major,
+            remaining: major as usize + 1,
+        }
+    }
+
+    fn y_range(&self) -> (usize, usize) {
+        (self.start.y.min(self.end.y), self.start.y.max(self.end.y))
+    }
+
+    fn x_range(&self) -> (usize, usize) {
+        (self.start.x.min(self.end.x), self.start.x.max(self.end.x))
+    }
+
+    // the inclusive run of x values that `points` produces on row `y`, worked out directly
+    fn row_span(&self, y: usize) -> Option<(usize, usize)> {
+        let (lo, hi) = self.y_range();
+        if y < lo || y > hi {
+            return None;
+        }
+
+        let (dx, dy, sx, sy) = self.deltas();
+        let j = (y as isize - self.start.y as isize) * sy;
+
+        // ceiling division for a positive divisor
+        let ceil = |a: isize, b: isize| -((-a).div_euclid(b));
+
+        let (i_lo, i_hi) = if dy == 0 {
+            (0, dx)
+        } else if dy > dx {
+            let i = (2 * j * dx + dy).div_euclid(2 * dy);
+            (i, i)
+        } else {
+            (ceil(2 * dx * j - dx, 2 * dy).max(0), (ceil(2 * dx * j + dx, 2 * dy) - 1).min(dx))
+        };
+
+        let a = (self.start.x as isize + sx * i_lo) as usize;
+        let b = (self.start.x as isize + sx * i_hi) as usize;
+        Some((a.min(b), a.max(b)))
+    }
+}
+
+// walks a segment of any integer slope with Bresenham's algorithm; the minor axis advances
+// whenever the exact line passes the midpoint between two cells
+struct Points {
+    x: isize,
+    y: isize,
+    sx: isize,
+    sy: isize,
+    x_major: bool,
+    major: isize,
+    minor: isize,
+    err: isize,
+    remaining: usize,
+}
+
+impl Iterator for Points {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let point = (self.x as usize, self.y as usize);
+        self.remaining -= 1;
+
+        self.err += 2 * self.minor;
+        let minor_step = self.err >= 2 * self.major;
+        if minor_step {
+            self.err -= 2 * self.major;
+        }
+
+        if self.x_major {
+            self.x += self.sx;
+            if minor_step { self.y += self.sy; }
         } else {
-            let x_values: Vec<usize> = if self.start.x < self.end.x {
-                (self.start.x ..= self.end.x).collect()
-            } else {
-                (self.end.x ..= self.start.x).rev().collect()
-            };
-
-            let y_values: Vec<usize> = if self.start.y < self.end.y {
-                (self.start.y ..= self.end.y).collect()
-            } else {
-                (self.end.y ..= self.start.y).rev().collect()
-            };
-
-            x_values.into_iter().zip(y_values.into_iter()).collect()
+            self.y += self.sy;
+            if minor_step { self.x += self.sx; }
         }
+
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
@@ -93,73 +170,82 @@ impl FromStr for LineSegment {
     }
 }
 
-struct Board {
-    inner: Vec<Vec<usize>>,
+// boards larger than this many cells are tracked sparsely
+const DENSE_LIMIT: usize = 1 << 24;
+
+enum Board {
+    Dense { width: usize, cells: Vec<u32> },
+    Sparse(HashMap<(usize, usize), u32>),
 }
 
 impl Board {
-    fn new() -> Self {
-        Self { inner: Vec::new() }
+    fn for_lines<'a>(lines: impl Iterator<Item = &'a LineSegment> + Clone) -> Self {
+        let width = lines.clone().map(|l| l.x_range().1 + 1).max().unwrap_or(0);
+        let height = lines.map(|l| l.y_range().1 + 1).max().unwrap_or(0);
+
+        match width.checked_mul(height) {
+            Some(size) if size <= DENSE_LIMIT => Board::Dense { width, cells: vec![0; size] },
+            _ => Board::Sparse(HashMap::new()),
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> u32 {
+        match self {
+            Board::Dense { width, cells } => cells.get(y * width + x).copied().unwrap_or(0),
+            Board::Sparse(cells) => cells.get(&(x, y)).copied().unwrap_or(0),
+        }
     }
 
     fn mark_line(&mut self, line: &LineSegment) {
         for (x, y) in line.points() {
-            let mut col = self.inner.get_mut(x);
-
-            if col.is_none() {
-                self.inner.resize(x + 1, Vec::new());
-                col = self.inner.get_mut(x);
+            match self {
+                Board::Dense { width, cells } => cells[y * *width + x] += 1,
+                Board::Sparse(cells) => *cells.entry((x, y)).or_insert(0) += 1,
             }
-
-            // safe due to the None check above
-            let col = col.unwrap();
-
-            let mut loc = col.get_mut(y);
-
-            if loc.is_none() {
-                col.resize(y + 1, 0);
-                loc = col.get_mut(y);
-            }
-
-            // safe due to the None check above
-            let loc = loc.unwrap();
-
-            *loc += 1;
         }
     }
 
     fn crossings(&self) -> usize {
-        self.inner.iter().fold(0, |acc, col| {
-            acc + col.iter().fold(0, |acc, loc_crossings| if *loc_crossings >= 2 { acc + 1 } else { acc })
-        })
+        match self {
+            Board::Dense { cells, .. } => cells.iter().filter(|c| **c >= 2).count(),
+            Board::Sparse(cells) => cells.values().filter(|c| **c >= 2).count(),
+        }
+    }
+
+    fn extent(&self) -> (usize, usize) {
+        match self {
+            Board::Dense { width, cells } => (*width, cells.len().checked_div(*width).unwrap_or(0)),
+            Board::Sparse(cells) => (
+                cells.keys().map(|(x, _)| x + 1).max().unwrap_or(0),
+                cells.keys().map(|(_, y)| y + 1).max().unwrap_or(0),
+            ),
+        }
     }
 }
 
-use std::fmt;
-impl fmt::Debug for Board {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for col in self.inner.iter() {
-            for loc in col {
-                if *loc > 0 {
-                    write!(f, "{}", loc)?;
-                } else {
-                    write!(f, ".")?;
+impl Render for Board {
+    fn render(&self, canvas: &mut Canvas) {
+        let (width, height) = self.extent();
+
+        for y in 0..height {
+            for x in 0..width {
+                match self.get(x, y) {
+                    0 => canvas.put(x, y, '.'),
+                    n => canvas.put(x, y, char::from_digit(n, 36).unwrap_or('+')),
                 }
             }
-            write!(f, "\n")?;
         }
-        Ok(())
     }
 }
 
 fn straight_lines(lines: &Vec<LineSegment>) -> usize {
-    let mut board = Board::new();
+    let mut board = Board::for_lines(lines.iter());
 
     for line in lines {
         if line.horizontal() || line.vertical() {
             //println!("drawing line: {:?}", line);
             board.mark_line(line);
-            //dbg!(&board);
+            //print!("{}", board.text());
         } else {
             //println!("skipping line because not horiz or vert: {:?}", line);
         }
@@ -169,7 +255,7 @@ fn straight_lines(lines: &Vec<LineSegment>) -> usize {
 }
 
 fn all_lines(lines: &Vec<LineSegment>) -> usize {
-    let mut board = Board::new();
+    let mut board = Board::for_lines(lines.iter());
 
     for line in lines {
         board.mark_line(line);
@@ -197,4 +283,35 @@ fn main() {
 
     println!("part 1: {}", straight_lines(&lines));
     println!("part 2: {}", all_lines(&lines));
+    println!("part 2 (sweep): {}", sweep::overlaps(&lines));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_sample() {
+        let lines: Vec<LineSegment> = include_str!("../../test.txt").lines()
+            .map(|l| l.parse().unwrap())
+            .collect();
+
+        let mut board = Board::for_lines(lines.iter());
+        for line in lines.iter() {
+            board.mark_line(line);
+        }
+
+        assert_eq!(board.text(), "\
+1.1....11.
+.111...2..
+..2.1.111.
+...1.2.2..
+.112313211
+...1.2....
+..1...1...
+.1.....1..
+1.......1.
+222111....
+");
+    }
 }
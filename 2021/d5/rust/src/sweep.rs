@@ -0,0 +1,122 @@
+use crate::LineSegment;
+
+// counts cells covered by at least two segments. the active set only changes on the rows where a
+// segment starts or ends, and each segment contributes a single x interval per row, so long
+// shallow segments cost no more than steep ones; a run of rows whose spans don't move between
+// two such rows is counted in one go
+pub fn overlaps(lines: &[LineSegment]) -> usize {
+    let mut order: Vec<&LineSegment> = lines.iter().collect();
+    order.sort_by_key(|l| l.y_range().0);
+
+    // a segment joins on its first row and leaves on the row after its last
+    let mut events: Vec<usize> = lines.iter()
+        .flat_map(|l| {
+            let (lo, hi) = l.y_range();
+            [lo, hi + 1]
+        })
+        .collect();
+    events.sort_unstable();
+    events.dedup();
+
+    let mut active: Vec<&LineSegment> = vec![];
+    let mut next = 0;
+    let mut total = 0;
+
+    for run in events.windows(2) {
+        let (from, to) = (run[0], run[1]);
+
+        active.retain(|l| l.y_range().1 >= from);
+        while next < order.len() && order[next].y_range().0 == from {
+            active.push(order[next]);
+            next += 1;
+        }
+
+        // unwrap: every active segment covers every row of the run
+        let spans = |y| active.iter().map(move |l| l.row_span(y).unwrap());
+
+        // spans only ever move one way, so matching ends mean nothing moved in between
+        if spans(from).eq(spans(to - 1)) {
+            total += (to - from) * doubled_length(spans(from));
+        } else {
+            total += (from..to).map(|y| doubled_length(spans(y))).sum::<usize>();
+        }
+    }
+
+    total
+}
+
+// the number of positions inside at least two of the given inclusive intervals
+fn doubled_length(spans: impl Iterator<Item = (usize, usize)>) -> usize {
+    let mut events: Vec<(usize, isize)> = spans
+        .flat_map(|(a, b)| [(a, 1), (b + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut depth = 0;
+    let mut last = 0;
+    let mut total = 0;
+
+    for (x, change) in events {
+        if depth >= 2 {
+            total += x - last;
+        }
+        depth += change;
+        last = x;
+    }
+
+    total
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lines(raw: &str) -> Vec<LineSegment> {
+        raw.lines().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn doubled() {
+        assert_eq!(doubled_length(vec![(0, 4), (2, 6), (3, 3)].into_iter()), 3);
+        assert_eq!(doubled_length(vec![(0, 1), (2, 3)].into_iter()), 0);
+    }
+
+    #[test]
+    fn sample() {
+        let lines = lines(include_str!("../../test.txt"));
+        assert_eq!(overlaps(&lines), 12);
+    }
+
+    #[test]
+    fn long_runs() {
+        let lines = lines("0,0 -> 0,1000000\n0,5 -> 0,2000000\n1,1999990 -> 0,1999990\n5,10 -> 5,10");
+        assert_eq!(overlaps(&lines), 999_997);
+    }
+
+    #[test]
+    fn matches_rasterizing() {
+        let lines = lines("0,0 -> 9,4\n9,0 -> 0,3\n2,7 -> 5,0\n4,0 -> 4,9\n0,2 -> 9,2\n7,7 -> 1,1\n3,9 -> 8,1");
+
+        let mut board = crate::Board::for_lines(lines.iter());
+        for line in lines.iter() {
+            board.mark_line(line);
+        }
+
+        assert_eq!(overlaps(&lines), board.crossings());
+    }
+
+    #[test]
+    fn row_span_matches_points() {
+        for raw in ["0,0 -> 9,4", "9,0 -> 0,3", "2,7 -> 5,0", "7,7 -> 1,1", "3,9 -> 8,1", "0,5 -> 9,5", "6,1 -> 0,4"] {
+            let line: LineSegment = raw.parse().unwrap();
+            let (lo, hi) = line.y_range();
+
+            for y in lo..=hi {
+                let xs: Vec<usize> = line.points().filter(|p| p.1 == y).map(|p| p.0).collect();
+                let span = (*xs.iter().min().unwrap(), *xs.iter().max().unwrap());
+                assert_eq!(line.row_span(y), Some(span), "{} at y={}", raw, y);
+                assert_eq!(xs.len(), span.1 - span.0 + 1, "{} at y={}", raw, y);
+            }
+        }
+    }
+}
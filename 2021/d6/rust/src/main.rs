@@ -1,5 +1,3 @@
-#![feature(test)]
-
 use std::path::Path;
 use std::fs::File;
 use std::io::Read;
@@ -7,6 +5,10 @@ use std::io::Read;
 const DEBUG: bool = false;
 use std::collections::VecDeque;
 
+mod recurrence;
+
+use recurrence::Lifecycle;
+
 fn simulate(fish: &Vec<usize>, days: usize) -> usize {
     let mut fish: VecDeque<usize> = fish.clone().into();
 
@@ -45,69 +47,83 @@ fn main() {
 
     let line = input.lines().next().expect("couldn't retreive data from file");
 
-    let fish = line.split(',')
-        .map(|n| n.parse::<usize>().expect("couldn't read fish age"))
-        .fold(vec![0; 9], |acc, x| {
-            let mut r = acc.clone();
-            r[x] += 1;
-            r
-        });
-
-    println!("part 1: {}", simulate(&fish, 80));
-    println!("part 2: {}", simulate(&fish, 256));
+    let lanternfish = Lifecycle::lanternfish();
+    let ages = line.split(',').map(|n| n.parse::<usize>().expect("couldn't read fish age"));
+    let timers = lanternfish.school(ages).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let fish: Vec<usize> = timers.iter().map(|&n| n as usize).collect();
+
+    for (part, days) in [(1, 80), (2, 256)] {
+        match lanternfish.population::<u64>(&timers, days) {
+            Ok(population) => println!("part {}: {}", part, population),
+            Err(e) => {
+                eprintln!("part {}: {}", part, e);
+                std::process::exit(1);
+            }
+        }
+    }
+    println!("part 2 (simulated): {}", simulate(&fish, 256));
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
     use std::collections::VecDeque;
 
-    use test::Bencher;
+    use super::Lifecycle;
 
-    const DATA: &'static str = include_str!("../../input.txt");
+    const DATA: &str = include_str!("../../input.txt");
     const DAYS: usize = 80;
 
-    #[bench]
-    fn simulate_pop(b: &mut Bencher) {
-        let mut fish: VecDeque<usize> = DATA.trim().split(',')
-            .map(|n| dbg!(n).parse::<usize>().expect("couldn't read fish age"))
-            .fold(vec![0; 9], |acc, x| {
-                let mut r = acc.clone();
-                r[x] += 1;
-                r
-            }).into();
-
-        b.iter(|| {
-            for x in 0..DAYS {
-                if let Some(new_parents) = fish.pop_front() {
-                    fish.resize(9, 0);
-
-                    fish[6] += new_parents;
-                    fish[8] = new_parents;
-                }
-            }
-        });
+    fn load() -> VecDeque<usize> {
+        let ages = DATA.trim().split(',').map(|n| n.parse::<usize>().expect("couldn't read fish age"));
+        Lifecycle::lanternfish().school(ages).unwrap()
+            .into_iter()
+            .map(|n| n as usize)
+            .collect()
+    }
 
-        println!("part 1: {}", fish.iter().sum::<usize>());
+    fn expected(fish: &VecDeque<usize>) -> usize {
+        let timers: Vec<u64> = fish.iter().map(|&n| n as u64).collect();
+        Lifecycle::lanternfish().population::<u64>(&timers, DAYS as u64).unwrap() as usize
     }
 
-    #[bench]
-    fn simulate_rotate(b: &mut Bencher) {
-        let mut fish: VecDeque<usize> = DATA.trim().split(',')
-            .map(|n| n.parse::<usize>().expect("couldn't read fish age"))
-            .fold(vec![0; 9], |acc, x| {
-                let mut r = acc.clone();
-                r[x] += 1;
-                r
-            }).into();
-
-        b.iter(|| {
-            for x in 0..DAYS {
-                fish.rotate_left(1);
-                fish[6] += fish[8];
+    #[test]
+    fn simulate_pop() {
+        let mut fish = load();
+        let expected = expected(&fish);
+
+        for _ in 0..DAYS {
+            if let Some(new_parents) = fish.pop_front() {
+                fish.resize(9, 0);
+
+                fish[6] += new_parents;
+                fish[8] = new_parents;
             }
-        });
+        }
+
+        assert_eq!(fish.iter().sum::<usize>(), expected);
+    }
+
+    #[test]
+    fn simulate_rotate() {
+        let mut fish = load();
+        let expected = expected(&fish);
+
+        for _ in 0..DAYS {
+            fish.rotate_left(1);
+            fish[6] += fish[8];
+        }
+
+        assert_eq!(fish.iter().sum::<usize>(), expected);
+    }
+
+    #[test]
+    fn simulate_matches() {
+        let fish: Vec<usize> = load().into();
+        let timers: Vec<u64> = fish.iter().map(|&n| n as u64).collect();
 
-        println!("part 1: {}", fish.iter().sum::<usize>());
+        assert_eq!(super::simulate(&fish, 256) as u64, Lifecycle::lanternfish().population::<u64>(&timers, 256).unwrap());
     }
 }
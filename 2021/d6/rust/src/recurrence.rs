@@ -0,0 +1,148 @@
+use std::fmt;
+
+use numeric::{Count, Matrix};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LifecycleError {
+    // a fish started with a timer no fish in this lifecycle can have
+    TimerOutOfRange { timer: usize, slots: usize },
+}
+
+impl fmt::Display for LifecycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LifecycleError::TimerOutOfRange { timer, slots } => {
+                write!(f, "a fish has timer {} but timers only run from 0 to {}", timer, slots - 1)
+            }
+        }
+    }
+}
+
+// The fish with timer t on day n + 1 are exactly the fish with timer t + 1 on day n, except that
+// every fish at 0 both resets to `reset` and spawns a new fish at `newborn`. That makes the
+// population a linear recurrence, stepped by a companion-style shift matrix whose powers give
+// the state after any number of days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lifecycle {
+    pub reset: usize,
+    pub newborn: usize,
+}
+
+impl Lifecycle {
+    pub fn lanternfish() -> Self {
+        Lifecycle { reset: 6, newborn: 8 }
+    }
+
+    // how many distinct timer values a fish can have
+    pub fn slots(&self) -> usize {
+        self.reset.max(self.newborn) + 1
+    }
+
+    // how many fish start on each timer, rejecting timers no fish in this lifecycle can have
+    pub fn school(&self, timers: impl IntoIterator<Item = usize>) -> Result<Vec<u64>, LifecycleError> {
+        let slots = self.slots();
+        let mut school = vec![0; slots];
+
+        for timer in timers {
+            match school.get_mut(timer) {
+                Some(count) => *count += 1,
+                None => return Err(LifecycleError::TimerOutOfRange { timer, slots }),
+            }
+        }
+
+        Ok(school)
+    }
+
+    pub fn transition<T: Count>(&self) -> Matrix<T> {
+        let mut m = Matrix::zeros(self.slots());
+
+        for t in 1..self.slots() {
+            m.set(t - 1, t, T::one());
+        }
+        for t in [self.reset, self.newborn] {
+            let cur = m.get(t, 0).plus(&T::one());
+            m.set(t, 0, cur);
+        }
+
+        m
+    }
+
+    // `timers[t]` is the number of fish starting with timer t
+    pub fn state_after<T: Count>(&self, timers: &[u64], days: u64) -> Result<Vec<T>, LifecycleError> {
+        let slots = self.slots();
+        if let Some(timer) = timers.iter().rposition(|&count| count > 0).filter(|&t| t >= slots) {
+            return Err(LifecycleError::TimerOutOfRange { timer, slots });
+        }
+
+        let mut start = vec![T::zero(); slots];
+        for (t, &count) in timers.iter().take(slots).enumerate() {
+            start[t] = T::from_u64(count);
+        }
+
+        Ok(self.transition::<T>().pow(days).apply(&start))
+    }
+
+    pub fn population<T: Count>(&self, timers: &[u64], days: u64) -> Result<T, LifecycleError> {
+        let state = self.state_after::<T>(timers, days)?;
+        Ok(state.iter().fold(T::zero(), |acc, c| acc.plus(c)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use numeric::{BigUint, Mod};
+
+    // 3,4,3,1,2
+    const SAMPLE: [u64; 5] = [0, 1, 1, 2, 1];
+
+    #[test]
+    fn sample() {
+        let fish = Lifecycle::lanternfish();
+        assert_eq!(fish.population::<u64>(&SAMPLE, 18).unwrap(), 26);
+        assert_eq!(fish.population::<u64>(&SAMPLE, 80).unwrap(), 5934);
+        assert_eq!(fish.population::<u64>(&SAMPLE, 256).unwrap(), 26984457539);
+        assert_eq!(fish.population::<BigUint>(&SAMPLE, 256).unwrap(), BigUint::from(26984457539u64));
+    }
+
+    #[test]
+    fn modular_matches_exact() {
+        let fish = Lifecycle::lanternfish();
+        let exact = fish.population::<BigUint>(&SAMPLE, 2000).unwrap();
+        let reduced = fish.population::<Mod<1_000_000_007>>(&SAMPLE, 2000).unwrap();
+
+        assert!(exact.bits() > 128);
+        let expected = exact.to_string().bytes()
+            .fold(0u64, |acc, d| (acc * 10 + (d - b'0') as u64) % 1_000_000_007);
+        assert_eq!(reduced, Mod(expected));
+    }
+
+    #[test]
+    fn custom_lifecycle() {
+        // every fish splits in two each day
+        let doubling = Lifecycle { reset: 0, newborn: 0 };
+        assert_eq!(doubling.population::<u64>(&[1], 10).unwrap(), 1024);
+
+        // resetting and spawning at 1 doubles the school every other day
+        let pairs = Lifecycle { reset: 1, newborn: 1 };
+        let counts: Vec<u64> = (0..8).map(|d| pairs.population(&[1], d).unwrap()).collect();
+        assert_eq!(counts, vec![1, 2, 2, 4, 4, 8, 8, 16]);
+    }
+
+    #[test]
+    fn school() {
+        let fish = Lifecycle::lanternfish();
+        assert_eq!(fish.school([3, 4, 3, 1, 2]), Ok(vec![0, 1, 1, 2, 1, 0, 0, 0, 0]));
+        assert_eq!(fish.school([3, 9, 4]), Err(LifecycleError::TimerOutOfRange { timer: 9, slots: 9 }));
+    }
+
+    #[test]
+    fn timer_out_of_range() {
+        let fish = Lifecycle::lanternfish();
+        assert_eq!(fish.population::<u64>(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 1], 1),
+            Err(LifecycleError::TimerOutOfRange { timer: 9, slots: 9 }));
+
+        // trailing empty timers are harmless
+        assert_eq!(fish.population::<u64>(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], 1), Ok(2));
+    }
+}
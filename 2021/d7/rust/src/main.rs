@@ -1,37 +1,15 @@
-#![feature(int_abs_diff)]
-
 use std::path::Path;
 use std::fs::File;
 use std::io::Read;
 
-fn linear_fuel_required(positions: &Vec<usize>, position: usize) -> usize {
-    positions.iter().fold(0, |acc, x| acc + x.abs_diff(position))
-}
-
-fn triangular_fuel_required(positions: &Vec<usize>, position: usize) -> usize {
-    positions.iter().fold(0, |acc, x| {
-        let dist = x.abs_diff(position);
-        acc + ((dist + 1) * dist) / 2 
-    })
-}
-
-fn best_position(positions: &Vec<usize>, fuel_required: fn(&Vec<usize>, usize) -> usize) -> usize {
-    let (min, max) = positions.iter().fold((usize::MAX, 0), |acc, x| {
-        let (min, max) = acc;
-        (min.min(*x), max.max(*x))
-    });
-
-    (min..=max).min_by_key(|x| fuel_required(positions, *x))
-        .map(|x| fuel_required(positions, x))
-        .expect("iterator guaranteed non-empty, unreachable") // iterator is guaranteed non-empty, so this is safe.
-}
+use rust::optimizer::{CrabError, Crabs, Cost};
 
-fn main() {
+fn main() -> Result<(), CrabError> {
     let mut args = std::env::args();
 
     let input_filename = args.nth(1).unwrap_or_else(|| {
         eprintln!("No input file specified");
-        eprintln!("Usage: ./run <input filename>");
+        eprintln!("Usage: ./run <input filename> [--brute]");
         std::process::exit(1);
     });
 
@@ -40,11 +18,12 @@ fn main() {
     input_file.read_to_string(&mut input).expect("failed to read from file");
 
     let line = input.lines().next().expect("couldn't retreive data from file");
+    let crabs: Crabs = line.parse()?;
 
-    let positions = line.split(',')
-        .map(|n| n.parse().expect("couldn't read fish age"))
-        .collect();
+    let brute = args.any(|a| a == "--brute");
+    let best = |cost| if brute { crabs.brute(cost) } else { crabs.optimize(cost) };
 
-    println!("part 1: {}", best_position(&positions, linear_fuel_required));
-    println!("part 2: {}", best_position(&positions, triangular_fuel_required));
+    println!("part 1: {}", best(Cost::Linear).cost);
+    println!("part 2: {}", best(Cost::Triangular).cost);
+    Ok(())
 }
@@ -0,0 +1,178 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrabError {
+    ParseError(String),
+    // there's no best position without any crabs
+    Empty,
+}
+
+impl fmt::Display for CrabError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrabError::ParseError(msg) => write!(f, "parse error: {}", msg),
+            CrabError::Empty => write!(f, "no crab positions"),
+        }
+    }
+}
+
+// what moving one crab a given distance costs; every cost has to be convex in the distance
+// for anything but brute force to find the true minimum
+#[derive(Debug, Clone, Copy)]
+pub enum Cost {
+    // one unit of fuel per step
+    Linear,
+    // one more unit of fuel for each step than the last
+    Triangular,
+    Custom(fn(usize) -> usize),
+}
+
+impl Cost {
+    pub fn of(&self, distance: usize) -> usize {
+        match self {
+            Cost::Linear => distance,
+            Cost::Triangular => distance * (distance + 1) / 2,
+            Cost::Custom(f) => f(distance),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Optimum {
+    pub position: usize,
+    pub cost: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crabs {
+    // kept sorted so the median is a lookup
+    positions: Vec<usize>,
+}
+
+impl Crabs {
+    pub fn new(mut positions: Vec<usize>) -> Result<Self, CrabError> {
+        if positions.is_empty() {
+            return Err(CrabError::Empty);
+        }
+        positions.sort_unstable();
+        Ok(Crabs { positions })
+    }
+
+    fn min(&self) -> usize {
+        self.positions[0]
+    }
+
+    fn max(&self) -> usize {
+        self.positions[self.positions.len() - 1]
+    }
+
+    // the total cost of moving every crab to `position`
+    pub fn total(&self, cost: Cost, position: usize) -> usize {
+        self.positions.iter().map(|x| cost.of(x.abs_diff(position))).sum()
+    }
+
+    fn at(&self, cost: Cost, position: usize) -> Optimum {
+        Optimum { position, cost: self.total(cost, position) }
+    }
+
+    // the lowest cheapest position, in closed form where the cost has one and by ternary search
+    // otherwise
+    pub fn optimize(&self, cost: Cost) -> Optimum {
+        match cost {
+            // any point between the two middle crabs is as good as any other, so take the lower
+            Cost::Linear => self.at(cost, self.positions[(self.positions.len() - 1) / 2]),
+            // the minimum of the continuous version is within half a step of the mean
+            Cost::Triangular => {
+                let mean = self.positions.iter().sum::<usize>() / self.positions.len();
+                self.scan(cost, mean.saturating_sub(1).max(self.min()), (mean + 1).min(self.max()))
+            }
+            Cost::Custom(_) => self.ternary(cost),
+        }
+    }
+
+    // narrows [min, max] by thirds, keeping the lowest minimum inside at every step
+    pub fn ternary(&self, cost: Cost) -> Optimum {
+        let (mut lo, mut hi) = (self.min(), self.max());
+
+        while hi - lo > 2 {
+            let m1 = lo + (hi - lo) / 3;
+            let m2 = hi - (hi - lo) / 3;
+            let (c1, c2) = (self.total(cost, m1), self.total(cost, m2));
+
+            if c1 > c2 {
+                // by convexity everything up to m1 costs at least as much as m1
+                lo = m1 + 1;
+            } else if c1 < c2 {
+                hi = m2 - 1;
+            } else {
+                hi = m2;
+            }
+        }
+
+        self.scan(cost, lo, hi)
+    }
+
+    // tries every position from the leftmost crab to the rightmost; for checking the others
+    pub fn brute(&self, cost: Cost) -> Optimum {
+        self.scan(cost, self.min(), self.max())
+    }
+
+    fn scan(&self, cost: Cost, lo: usize, hi: usize) -> Optimum {
+        // unwrap: callers always pass lo <= hi
+        (lo..=hi).map(|p| self.at(cost, p)).min_by_key(|o| o.cost).unwrap()
+    }
+}
+
+impl std::str::FromStr for Crabs {
+    type Err = CrabError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let positions = s.trim()
+            .split(',')
+            .filter(|n| !n.is_empty())
+            .map(|n| n.trim().parse().map_err(|_| CrabError::ParseError(format!("bad position {:?}", n))))
+            .collect::<Result<_, _>>()?;
+        Crabs::new(positions)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SAMPLE: &str = "16,1,2,0,4,2,7,1,2,14";
+
+    #[test]
+    fn sample() {
+        let crabs: Crabs = SAMPLE.parse().unwrap();
+        assert_eq!(crabs.optimize(Cost::Linear), Optimum { position: 2, cost: 37 });
+        assert_eq!(crabs.optimize(Cost::Triangular), Optimum { position: 5, cost: 168 });
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let costs = [
+            Cost::Linear,
+            Cost::Triangular,
+            Cost::Custom(|d| d * d * d),
+            // flat near the crab, so there are runs of equally good positions
+            Cost::Custom(|d| d.saturating_sub(3)),
+        ];
+        let sets: [&[usize]; 5] = [&[0], &[3, 3], &[1, 100], &[0, 0, 0, 9, 50, 51], &[5, 1, 8, 13, 2, 2, 40, 7]];
+
+        for positions in sets {
+            let crabs = Crabs::new(positions.to_vec()).unwrap();
+            for cost in costs {
+                let expected = crabs.brute(cost);
+                assert_eq!(crabs.optimize(cost), expected, "{:?} {:?}", positions, cost);
+                assert_eq!(crabs.ternary(cost), expected, "{:?} {:?}", positions, cost);
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("".parse::<Crabs>(), Err(CrabError::Empty));
+        assert!(matches!("1,x".parse::<Crabs>(), Err(CrabError::ParseError(_))));
+    }
+}
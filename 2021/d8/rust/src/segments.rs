@@ -0,0 +1,289 @@
+use std::fmt;
+
+// a set of segments or wires, one bit each
+pub type Mask = u32;
+
+// the segments lit for each digit on a standard display, indexed by the digit's value
+pub const STANDARD: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    UnknownSegment(char),
+    BadEntry(String),
+    // two digits that light the same segments, so a pattern couldn't say which it is
+    DuplicateDigit(String),
+    // no wiring turns every pattern into a digit
+    Contradiction,
+    // more than one wiring does
+    Ambiguous,
+    // an output pattern that doesn't decode to any digit under the wiring
+    UnknownDigit(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::UnknownSegment(c) => write!(f, "unknown segment {:?}", c),
+            SolveError::BadEntry(line) => write!(f, "couldn't parse entry {:?}", line),
+            SolveError::DuplicateDigit(pattern) => write!(f, "more than one digit lights {:?}", pattern),
+            SolveError::Contradiction => write!(f, "no wiring explains every pattern"),
+            SolveError::Ambiguous => write!(f, "more than one wiring explains every pattern"),
+            SolveError::UnknownDigit(pattern) => write!(f, "{:?} isn't a digit", pattern),
+        }
+    }
+}
+
+// the segment names a display uses and which of them light up for each digit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SevenSegment {
+    alphabet: Vec<char>,
+    digits: Vec<Mask>,
+}
+
+impl SevenSegment {
+    pub fn standard() -> Self {
+        // unwrap: the standard table only uses its own alphabet
+        SevenSegment::new("abcdefg", &STANDARD).unwrap()
+    }
+
+    pub fn new(alphabet: &str, digits: &[&str]) -> Result<Self, SolveError> {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+        assert!(alphabet.len() <= Mask::BITS as usize, "too many segments for a mask");
+
+        let mut display = SevenSegment { alphabet, digits: vec![] };
+        display.digits = digits.iter().map(|d| display.mask(d)).collect::<Result<_, _>>()?;
+
+        for (i, &d) in display.digits.iter().enumerate() {
+            if display.digits[..i].contains(&d) {
+                return Err(SolveError::DuplicateDigit(display.pattern(d)));
+            }
+        }
+        Ok(display)
+    }
+
+    fn full(&self) -> Mask {
+        Mask::MAX.checked_shr(Mask::BITS - self.alphabet.len() as u32).unwrap_or(0)
+    }
+
+    pub fn mask(&self, pattern: &str) -> Result<Mask, SolveError> {
+        pattern.chars().try_fold(0, |acc, c| {
+            let idx = self.alphabet.iter().position(|&a| a == c).ok_or(SolveError::UnknownSegment(c))?;
+            Ok(acc | 1 << idx)
+        })
+    }
+
+    pub fn pattern(&self, mask: Mask) -> String {
+        self.alphabet.iter().enumerate().filter(|&(i, _)| mask & 1 << i != 0).map(|(_, &c)| c).collect()
+    }
+
+    // the digits that light up a given number of segments
+    fn digits_with(&self, lit: u32) -> impl Iterator<Item = Mask> + '_ {
+        self.digits.iter().cloned().filter(move |d| d.count_ones() == lit)
+    }
+
+    // true for patterns whose segment count alone says which digit they are
+    pub fn is_unique_length(&self, pattern: Mask) -> bool {
+        self.digits_with(pattern.count_ones()).count() == 1
+    }
+
+    pub fn digit(&self, segments: Mask) -> Option<usize> {
+        self.digits.iter().position(|&d| d == segments)
+    }
+
+    pub fn parse_entry(&self, line: &str) -> Result<Entry, SolveError> {
+        let bad = || SolveError::BadEntry(line.to_string());
+        let (patterns, outputs) = line.split_once(" | ").ok_or_else(bad)?;
+        let masks = |s: &str| s.split_whitespace().map(|p| self.mask(p)).collect::<Result<Vec<_>, _>>();
+
+        Ok(Entry { patterns: masks(patterns)?, outputs: masks(outputs)? })
+    }
+
+    // finds the one way the wires can be connected to segments that makes every pattern a digit
+    pub fn solve(&self, patterns: &[Mask]) -> Result<Wiring, SolveError> {
+        let n = self.alphabet.len();
+        let mut solver = Solver { display: self, patterns, found: vec![] };
+        solver.search(vec![self.full(); n]);
+
+        match solver.found.len() {
+            0 => Err(SolveError::Contradiction),
+            1 => Ok(Wiring { segment: solver.found.remove(0) }),
+            _ => Err(SolveError::Ambiguous),
+        }
+    }
+
+    pub fn decode(&self, wiring: &Wiring, pattern: Mask) -> Result<usize, SolveError> {
+        self.digit(wiring.map(pattern)).ok_or_else(|| SolveError::UnknownDigit(self.pattern(pattern)))
+    }
+
+    // the outputs read as one number, in the base of however many digits the display has
+    pub fn value(&self, entry: &Entry) -> Result<usize, SolveError> {
+        let wiring = self.solve(&entry.patterns)?;
+        entry.outputs.iter().try_fold(0, |acc, &p| Ok(acc * self.digits.len() + self.decode(&wiring, p)?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub patterns: Vec<Mask>,
+    pub outputs: Vec<Mask>,
+}
+
+// which segment each wire is connected to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wiring {
+    segment: Vec<usize>,
+}
+
+impl Wiring {
+    pub fn map(&self, wires: Mask) -> Mask {
+        self.segment.iter().enumerate()
+            .filter(|&(w, _)| wires & 1 << w != 0)
+            .fold(0, |acc, (_, &s)| acc | 1 << s)
+    }
+}
+
+struct Solver<'a> {
+    display: &'a SevenSegment,
+    patterns: &'a [Mask],
+    found: Vec<Vec<usize>>,
+}
+
+impl<'a> Solver<'a> {
+    // narrows the segments each wire could be until nothing changes, returning false once a
+    // wire has no options left
+    fn propagate(&self, cand: &mut [Mask]) -> bool {
+        let full = self.display.full();
+
+        loop {
+            let before = cand.to_vec();
+
+            for &p in self.patterns {
+                // the digits this pattern could still be, given what each wire could be
+                let (mut lit, mut dark) = (0, 0);
+                for d in self.display.digits_with(p.count_ones()) {
+                    let fits = cand.iter().enumerate().all(|(w, &c)| {
+                        if p & 1 << w != 0 { c & d != 0 } else { c & !d & full != 0 }
+                    });
+                    if fits {
+                        lit |= d;
+                        dark |= !d & full;
+                    }
+                }
+
+                for (w, c) in cand.iter_mut().enumerate() {
+                    *c &= if p & 1 << w != 0 { lit } else { dark };
+                }
+            }
+
+            // a wire pinned to one segment rules that segment out for every other wire
+            for w in 0..cand.len() {
+                if cand[w].count_ones() == 1 {
+                    let pinned = cand[w];
+                    for (v, c) in cand.iter_mut().enumerate() {
+                        if v != w {
+                            *c &= !pinned;
+                        }
+                    }
+                }
+            }
+
+            if cand.contains(&0) {
+                return false;
+            }
+            if cand == before.as_slice() {
+                return true;
+            }
+        }
+    }
+
+    // branches on the least certain wire whenever propagation stalls, stopping after a second
+    // wiring turns up since that's already enough to call it ambiguous
+    fn search(&mut self, mut cand: Vec<Mask>) {
+        if self.found.len() > 1 || !self.propagate(&mut cand) {
+            return;
+        }
+
+        let open = (0..cand.len()).filter(|&w| cand[w].count_ones() > 1).min_by_key(|&w| cand[w].count_ones());
+        match open {
+            Some(w) => {
+                for s in 0..Mask::BITS {
+                    if cand[w] & 1 << s != 0 {
+                        let mut next = cand.clone();
+                        next[w] = 1 << s;
+                        self.search(next);
+                    }
+                }
+            }
+            None => {
+                let segment: Vec<usize> = cand.iter().map(|c| c.trailing_zeros() as usize).collect();
+                let wiring = Wiring { segment: segment.clone() };
+                if self.patterns.iter().all(|&p| self.display.digit(wiring.map(p)).is_some()) {
+                    self.found.push(segment);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SAMPLE: &str = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+    #[test]
+    fn sample() {
+        let display = SevenSegment::standard();
+        let entry = display.parse_entry(SAMPLE).unwrap();
+        let wiring = display.solve(&entry.patterns).unwrap();
+
+        // d is wired to a, e to b and so on
+        assert_eq!(display.pattern(wiring.map(display.mask("d").unwrap())), "a");
+        assert_eq!(display.pattern(wiring.map(display.mask("g").unwrap())), "e");
+        assert_eq!(display.value(&entry), Ok(5353));
+    }
+
+    #[test]
+    fn unique_lengths() {
+        let display = SevenSegment::standard();
+        let unique: Vec<usize> = (0..10).filter(|&d| display.is_unique_length(display.digits[d])).collect();
+        assert_eq!(unique, [1, 4, 7, 8]);
+    }
+
+    #[test]
+    fn errors() {
+        let display = SevenSegment::standard();
+        assert_eq!(display.parse_entry("abz | ab"), Err(SolveError::UnknownSegment('z')));
+        assert!(matches!(display.parse_entry("ab ab"), Err(SolveError::BadEntry(_))));
+
+        // only the two-segment pattern: c and f can be swapped freely
+        assert_eq!(display.solve(&[display.mask("ab").unwrap()]), Err(SolveError::Ambiguous));
+        // two different two-segment patterns can't both be a 1
+        let patterns = [display.mask("ab").unwrap(), display.mask("cd").unwrap()];
+        assert_eq!(display.solve(&patterns), Err(SolveError::Contradiction));
+
+        let entry = display.parse_entry(SAMPLE).unwrap();
+        let wiring = display.solve(&entry.patterns).unwrap();
+        assert_eq!(display.decode(&wiring, display.mask("a").unwrap()), Err(SolveError::UnknownDigit("a".to_string())));
+    }
+
+    #[test]
+    fn other_displays() {
+        // a three-segment display counting in base 4: nothing, left, right, both lit
+        let display = SevenSegment::new("xyz", &["", "x", "y", "xy"]).unwrap();
+        let entry = display.parse_entry("z y zy | zy z y").unwrap();
+        assert_eq!(display.solve(&entry.patterns), Err(SolveError::Ambiguous));
+
+        // base 3, with the patterns differing in size so only one wiring fits
+        let display = SevenSegment::new("xyz", &["z", "xz", "xyz"]).unwrap();
+        let entry = display.parse_entry("x xy xyz | xy x xyz").unwrap();
+        let wiring = display.solve(&entry.patterns).unwrap();
+        assert_eq!(display.pattern(wiring.map(display.mask("y").unwrap())), "x");
+        assert_eq!(display.value(&entry), Ok(11));
+
+        // segment order doesn't matter, so "zx" is the same digit as "xz"
+        assert_eq!(SevenSegment::new("xyz", &["z", "xz", "zx"]), Err(SolveError::DuplicateDigit("xz".to_string())));
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::fmt;
+
+// the height that walls basins off from each other
+pub const RIDGE: u8 = 9;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    ParseError(String),
+    // a row whose length differs from the first
+    Ragged(usize),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::ParseError(msg) => write!(f, "parse error: {}", msg),
+            MapError::Ragged(row) => write!(f, "row {} is a different length to the first", row),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    // up, down, left and right
+    Four,
+    // diagonals as well
+    Eight,
+}
+
+impl Connectivity {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeightMap {
+    width: usize,
+    height: usize,
+    heights: Vec<u8>,
+}
+
+impl HeightMap {
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.heights[y * self.width + x]
+    }
+
+    fn neighbors(&self, cell: usize, conn: Connectivity) -> impl Iterator<Item = usize> + '_ {
+        let (x, y) = ((cell % self.width) as isize, (cell / self.width) as isize);
+        conn.offsets().iter().filter_map(move |&(dx, dy)| {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || ny < 0 || nx >= self.width as isize || ny >= self.height as isize {
+                None
+            } else {
+                Some(ny as usize * self.width + nx as usize)
+            }
+        })
+    }
+
+    fn coords(&self, cell: usize) -> (usize, usize) {
+        (cell % self.width, cell / self.width)
+    }
+
+    // cells strictly lower than every neighbour
+    pub fn low_points(&self, conn: Connectivity) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.heights.len())
+            .filter(move |&c| self.neighbors(c, conn).all(|n| self.heights[c] < self.heights[n]))
+            .map(move |c| self.coords(c))
+    }
+
+    pub fn risk(&self, conn: Connectivity) -> usize {
+        self.low_points(conn).map(|(x, y)| self.get(x, y) as usize + 1).sum()
+    }
+
+    // groups every cell below the ridge height into basins, joining neighbours with a union-find
+    // pass rather than a flood fill so the heights are left alone and nothing recurses
+    pub fn basins(&self, conn: Connectivity) -> Labelling {
+        let mut sets = DisjointSet::new(self.heights.len());
+        for cell in 0..self.heights.len() {
+            if self.heights[cell] >= RIDGE {
+                continue;
+            }
+            for n in self.neighbors(cell, conn) {
+                if self.heights[n] < RIDGE {
+                    sets.union(cell, n);
+                }
+            }
+        }
+
+        // labels are handed out in the order each basin's first cell appears
+        let mut label_of: HashMap<usize, usize> = HashMap::new();
+        let mut labels = vec![None; self.heights.len()];
+        let mut basins: Vec<Basin> = vec![];
+
+        for (cell, &h) in self.heights.iter().enumerate() {
+            if h >= RIDGE {
+                continue;
+            }
+            let root = sets.find(cell);
+            let label = *label_of.entry(root).or_insert_with(|| {
+                basins.push(Basin { label: basins.len(), size: 0, low_point: self.coords(cell), boundary: vec![] });
+                basins.len() - 1
+            });
+            labels[cell] = Some(label);
+
+            let basin = &mut basins[label];
+            basin.size += 1;
+            let (lx, ly) = basin.low_point;
+            if h < self.get(lx, ly) {
+                basin.low_point = self.coords(cell);
+            }
+        }
+
+        // the ridge cells touching each basin, in reading order
+        for cell in 0..self.heights.len() {
+            if self.heights[cell] < RIDGE {
+                continue;
+            }
+            let mut touching: Vec<usize> = self.neighbors(cell, conn).filter_map(|n| labels[n]).collect();
+            touching.sort_unstable();
+            touching.dedup();
+            for label in touching {
+                basins[label].boundary.push(self.coords(cell));
+            }
+        }
+
+        Labelling { width: self.width, labels, basins }
+    }
+}
+
+impl std::str::FromStr for HeightMap {
+    type Err = MapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut heights = vec![];
+
+        for (row, line) in s.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+            let line = line.trim();
+            if *width.get_or_insert(line.len()) != line.len() {
+                return Err(MapError::Ragged(row));
+            }
+            for c in line.chars() {
+                let h = c.to_digit(10).ok_or_else(|| MapError::ParseError(format!("bad height {:?}", c)))?;
+                heights.push(h as u8);
+            }
+        }
+
+        let width = width.unwrap_or(0);
+        let height = heights.len().checked_div(width).unwrap_or(0);
+        Ok(HeightMap { width, height, heights })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basin {
+    pub label: usize,
+    pub size: usize,
+    // the lowest cell, the first in reading order if there's a tie
+    pub low_point: (usize, usize),
+    // the ridge cells next to the basin
+    pub boundary: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Labelling {
+    width: usize,
+    // None for ridge cells
+    labels: Vec<Option<usize>>,
+    basins: Vec<Basin>,
+}
+
+impl Labelling {
+    pub fn label(&self, x: usize, y: usize) -> Option<usize> {
+        self.labels[y * self.width + x]
+    }
+
+    pub fn basins(&self) -> &[Basin] {
+        &self.basins
+    }
+
+    // the sizes of the n biggest basins, biggest first
+    pub fn largest(&self, n: usize) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.basins.iter().map(|b| b.size).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.truncate(n);
+        sizes
+    }
+}
+
+// a disjoint-set forest with union by size and path halving
+struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        DisjointSet { parent: (0..n).collect(), size: vec![1; n] }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> HeightMap {
+        include_str!("../../test.txt").parse().unwrap()
+    }
+
+    #[test]
+    fn sample_basins() {
+        let map = sample();
+        let labelling = map.basins(Connectivity::Four);
+
+        assert_eq!(map.risk(Connectivity::Four), 15);
+        assert_eq!(labelling.largest(3), [14, 9, 9]);
+        assert_eq!(labelling.basins().len(), 4);
+
+        // the top left basin
+        let basin = &labelling.basins()[0];
+        assert_eq!((basin.size, basin.low_point), (3, (1, 0)));
+        assert_eq!(basin.boundary, [(2, 0), (1, 1), (0, 2)]);
+        assert_eq!(labelling.label(0, 0), Some(0));
+        assert_eq!(labelling.label(2, 0), None);
+
+        // a basin's low point always carries its own label
+        for basin in labelling.basins() {
+            let (x, y) = basin.low_point;
+            assert_eq!(labelling.label(x, y), Some(basin.label));
+        }
+
+        // heights survive labelling
+        assert_eq!(map, sample());
+    }
+
+    #[test]
+    fn eight_connectivity() {
+        let map: HeightMap = "19\n91\n".parse().unwrap();
+        assert_eq!(map.basins(Connectivity::Four).largest(3), [1, 1]);
+        assert_eq!(map.basins(Connectivity::Eight).largest(3), [2]);
+        assert_eq!(map.low_points(Connectivity::Eight).count(), 0);
+    }
+
+    #[test]
+    fn large_map() {
+        // one basin snaking through a map big enough to blow a recursive fill's stack
+        let size = 1000;
+        let input: String = (0..size)
+            .map(|y| match y % 4 {
+                1 => "9".repeat(size - 1) + "0",
+                3 => "0".to_string() + &"9".repeat(size - 1),
+                _ => "0".repeat(size),
+            } + "\n")
+            .collect();
+        let map: HeightMap = input.parse().unwrap();
+        let labelling = map.basins(Connectivity::Four);
+        assert_eq!(labelling.basins().len(), 1);
+        assert_eq!(labelling.basins()[0].size, size * size / 2 + size / 2);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("123\n12\n".parse::<HeightMap>(), Err(MapError::Ragged(1)));
+        assert!(matches!("1x\n".parse::<HeightMap>(), Err(MapError::ParseError(_))));
+    }
+}
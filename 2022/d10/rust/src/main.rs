@@ -1,6 +1,8 @@
 use std::num::ParseIntError;
 use std::str::FromStr;
 
+use render::{Canvas, Render};
+
 const TEST: &'static str = include_str!("../../test");
 const INPUT: &'static str = include_str!("../../input");
 
@@ -82,28 +84,32 @@ fn part1(commands: &[Command]) -> isize {
     signal_sum
 }
 
-fn part2(commands: &[Command]) {
-    let mut cycle = 1;
+struct Crt {
+    pixels: Vec<bool>,
+}
+
+impl Render for Crt {
+    fn render(&self, canvas: &mut Canvas) {
+        for (idx, lit) in self.pixels.iter().enumerate() {
+            canvas.put(idx % 40, idx / 40, if *lit { '#' } else { ' ' });
+        }
+    }
+}
+
+fn part2(commands: &[Command]) -> Crt {
+    let mut pixels = vec![];
     let mut reg_x = 1;
 
     for command in commands {
         for _ in 0..command.cycles() {
-            if reg_x >= (cycle % 40) - 2 && reg_x <= (cycle % 40) {
-                print!("#");
-            } else {
-                print!(" ");
-            }
-
-            if cycle % 40 == 0 {
-                println!();
-            }
-
-            cycle += 1;
+            let col = (pixels.len() % 40) as isize;
+            pixels.push(reg_x >= col - 1 && reg_x <= col + 1);
         }
 
         command.apply(&mut reg_x);
     }
-    println!()
+
+    Crt { pixels }
 }
 
 fn main() -> Result<(), Day10Error> {
@@ -111,7 +117,7 @@ fn main() -> Result<(), Day10Error> {
     let input = parse(INPUT)?;
     println!("part1 (test): {}", part1(&test));
     println!("part1 (actual): {}", part1(&input));
-    part2(&test);
-    part2(&input);
+    println!("part2 (test):\n{}", part2(&test).ascii(false));
+    println!("part2 (actual):\n{}", part2(&input).ascii(false));
     Ok( () )
 }
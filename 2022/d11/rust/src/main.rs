@@ -1,7 +1,10 @@
 use std::collections::VecDeque;
+use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
+use sections::{InputError, SectionError, Sections};
+
 const TEST: &'static str = include_str!("../../test");
 const INPUT: &'static str = include_str!("../../input");
 
@@ -10,6 +13,26 @@ enum Day11Error {
     ParseError(String),
 }
 
+impl fmt::Display for Day11Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Day11Error::ParseError(msg) => write!(f, "parse error: {}", msg),
+        }
+    }
+}
+
+impl From<SectionError> for Day11Error {
+    fn from(error: SectionError) -> Self {
+        Self::ParseError(error.to_string())
+    }
+}
+
+impl From<InputError<Day11Error>> for Day11Error {
+    fn from(error: InputError<Day11Error>) -> Self {
+        Self::ParseError(error.to_string())
+    }
+}
+
 impl From<ParseIntError> for Day11Error {
     fn from(error: ParseIntError) -> Self {
         Self::ParseError(format!("{}", error))
@@ -169,30 +192,41 @@ impl Monkey {
     }
 }
 
-fn parse(raw: &str) -> Result<Vec<Monkey>, Day11Error> {
-    // investigate Iterator::array_chunks when it's stablized.
-    let lines = raw.lines().collect::<Vec<_>>();
-    let mut monkeys = vec![];
+fn parse_monkey(lines: &[&str]) -> Result<Monkey, (usize, Day11Error)> {
+    if lines.len() != 5 {
+        return Err((0, Day11Error::ParseError(format!("expected 5 lines after the header, found {}", lines.len()))));
+    }
 
-    for chunk in lines.chunks(7) {
-        let mut held_items = VecDeque::new();
-        if let Some(raw_item_list) = chunk[1].trim().strip_prefix("Starting items: ") {
-            for raw_item in raw_item_list.split(", ") {
-                held_items.push_back(raw_item.parse()?);
-            }
-        } else {
-            return Err(Day11Error::ParseError(format!("items line didn't start with 'Starting items: '")));
+    let mut held_items = VecDeque::new();
+    if let Some(raw_item_list) = lines[0].trim().strip_prefix("Starting items: ") {
+        for raw_item in raw_item_list.split(", ") {
+            held_items.push_back(raw_item.parse().map_err(|e| (0, e))?);
         }
+    } else {
+        return Err((0, Day11Error::ParseError("items line didn't start with 'Starting items: '".to_string())));
+    }
 
-        let operation = chunk[2].parse()?;
-        let test = chunk[3..=5].try_into()?;
+    let operation = lines[1].parse().map_err(|e| (1, e))?;
+    // the test's error doesn't say which of its three lines was bad, so blame the first
+    let test = lines[2..=4].try_into().map_err(|e| (2, e))?;
 
-        monkeys.push(Monkey {
-            held_items,
-            times_inspected: 0,
-            operation,
-            test,
-        });
+    Ok(Monkey {
+        held_items,
+        times_inspected: 0,
+        operation,
+        test,
+    })
+}
+
+fn parse(raw: &str) -> Result<Vec<Monkey>, Day11Error> {
+    let sections = Sections::new(raw).headed()?;
+    let mut monkeys = vec![];
+
+    for (idx, section) in sections.iter().enumerate() {
+        if section.name() != format!("Monkey {}", idx) {
+            return Err(section.error_at(0, Day11Error::ParseError(format!("expected monkey {}", idx))).into());
+        }
+        monkeys.push(section.parse_with(parse_monkey)?);
     }
 
     Ok(monkeys)
@@ -223,8 +257,8 @@ fn part1(monkeys: &[Monkey]) -> usize {
 
 fn part2(monkeys: &[Monkey]) -> usize {
     let mut monkeys = monkeys.to_owned();
-    // unwrap is safe because we always have monkeys
-    let worry_settle_factor = monkeys.iter().map(|m| m.test.div_by).product::<usize>();
+    // every test only looks at worry modulo its divisor, so worry can be kept modulo their lcm
+    let worry_settle_factor = monkeys.iter().fold(1, |acc, m| numeric::lcm(acc, m.test.div_by as u64)) as usize;
 
     for _ in 1..=10000 {
         for idx in 0..monkeys.len() {
@@ -3,6 +3,8 @@ use std::fmt;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
+use sections::{InputError, Sections};
+
 const TEST: &'static str = include_str!("../../test");
 const INPUT: &'static str = include_str!("../../input");
 
@@ -12,6 +14,21 @@ enum Day13Error {
     RuntimeError(String),
 }
 
+impl fmt::Display for Day13Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Day13Error::ParseError(msg) => write!(f, "parse error: {}", msg),
+            Day13Error::RuntimeError(msg) => write!(f, "runtime error: {}", msg),
+        }
+    }
+}
+
+impl From<InputError<Day13Error>> for Day13Error {
+    fn from(error: InputError<Day13Error>) -> Self {
+        Self::ParseError(error.to_string())
+    }
+}
+
 impl From<ParseIntError> for Day13Error {
     fn from(error: ParseIntError) -> Self {
         Self::ParseError(format!("{}", error))
@@ -124,11 +141,13 @@ impl fmt::Debug for Packet {
 
 fn parse(raw: &str) -> Result<Vec<Packet>, Day13Error> {
     let mut packets = Vec::new();
-    let lines = raw.lines().collect::<Vec<_>>();
 
-    for raw_pair in lines.chunks(3) {
-        packets.push(raw_pair[0].parse()?);
-        packets.push(raw_pair[1].parse()?);
+    for raw_pair in &Sections::new(raw) {
+        if raw_pair.len() != 2 {
+            let error = Day13Error::ParseError(format!("expected a pair of packets, found {}", raw_pair.len()));
+            return Err(raw_pair.error_at(0, error).into());
+        }
+        packets.extend(raw_pair.parse_lines(str::parse)?);
     }
 
     Ok(packets)
@@ -0,0 +1,350 @@
+use std::collections::HashSet;
+
+use render::{Canvas, Render};
+
+pub const DEFAULT_SOURCE: (usize, usize) = (500, 0);
+
+#[derive(Debug, Clone)]
+struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        BitGrid { width, height, stride, words: vec![0; stride * height] }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.words[y * self.stride + x / 64] & (1 << (x % 64)) != 0
+    }
+
+    fn set(&mut self, x: usize, y: usize) {
+        self.words[y * self.stride + x / 64] |= 1 << (x % 64);
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    // the bits of the last word in a row that are inside the grid
+    fn tail_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => !0,
+            n => (1 << n) - 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    // x may be negative when a floor lets sand spread left of the origin
+    Settled(isize, usize),
+    // the grain fell out of the cave
+    Abyss,
+    // the source is already covered in sand
+    Blocked,
+}
+
+#[derive(Debug, Clone)]
+struct Source {
+    x: usize,
+    y: usize,
+    // the route the previous grain took, in window coordinates; the next grain resumes from
+    // the deepest cell on it that is still open
+    path: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cave {
+    rock: BitGrid,
+    filled: BitGrid,
+    x0: isize,
+    floor: Option<usize>,
+    sources: Vec<Source>,
+    next: usize,
+    settled: usize,
+}
+
+impl Cave {
+    // `floor` is the absolute y of an infinite floor; without one, sand falling below the
+    // lowest rock is lost to the abyss
+    pub fn new(rocks: &HashSet<(usize, usize)>, sources: &[(usize, usize)], floor: Option<usize>) -> Self {
+        let xs = || rocks.iter().chain(sources.iter()).map(|&(x, _)| x as isize);
+
+        let mut lo = xs().min().unwrap_or(0);
+        let mut hi = xs().max().unwrap_or(0);
+
+        let height = match floor {
+            Some(floor) => {
+                // sand spreads at most one column per row it falls, which can take it past x = 0
+                for &(sx, sy) in sources {
+                    let spread = floor.saturating_sub(sy) as isize;
+                    lo = lo.min(sx as isize - spread);
+                    hi = hi.max(sx as isize + spread);
+                }
+                floor
+            }
+            None => {
+                lo -= 1;
+                hi += 1;
+                rocks.iter().map(|&(_, y)| y + 1).max().unwrap_or(0)
+            }
+        };
+
+        let width = (hi - lo + 1) as usize;
+        let column = |x: usize| (x as isize - lo) as usize;
+
+        let mut rock = BitGrid::new(width, height);
+        for &(x, y) in rocks.iter().filter(|&&(_, y)| y < height) {
+            rock.set(column(x), y);
+        }
+
+        Cave {
+            filled: rock.clone(),
+            rock,
+            x0: lo,
+            floor,
+            sources: sources.iter().map(|&(x, y)| Source { x: column(x), y, path: vec![] }).collect(),
+            next: 0,
+            settled: 0,
+        }
+    }
+
+    // the cells the last grain from `source` passed through that are still open
+    pub fn path(&self, source: usize) -> impl Iterator<Item = (isize, usize)> + '_ {
+        self.sources[source].path.iter()
+            .filter(move |&&(x, y)| !self.filled.get(x, y))
+            .map(move |&(x, y)| (x as isize + self.x0, y))
+    }
+
+    // drops a single grain from the next source in turn
+    pub fn step(&mut self) -> Step {
+        if self.sources.is_empty() {
+            return Step::Blocked;
+        }
+
+        let source = self.next;
+        self.next = (self.next + 1) % self.sources.len();
+        self.drop_from(source)
+    }
+
+    pub fn drop_from(&mut self, source: usize) -> Step {
+        let Cave { filled, sources, floor, .. } = self;
+        let src = &mut sources[source];
+        let (width, height) = (filled.width, filled.height);
+
+        if src.x >= width || src.y >= height || filled.get(src.x, src.y) {
+            return Step::Blocked;
+        }
+
+        if let Some(idx) = src.path.iter().position(|&(x, y)| filled.get(x, y)) {
+            src.path.truncate(idx);
+        }
+        if src.path.is_empty() {
+            src.path.push((src.x, src.y));
+        }
+
+        loop {
+            // unwrap: the path always holds at least the source
+            let &(x, y) = src.path.last().unwrap();
+
+            if y + 1 == height {
+                if floor.is_none() {
+                    return Step::Abyss;
+                }
+            } else {
+                let below = [Some(x), x.checked_sub(1), Some(x + 1)];
+                let next = below.iter().find(|nx| match nx {
+                    Some(nx) if *nx < width => !filled.get(*nx, y + 1),
+                    _ => true,
+                });
+
+                match next {
+                    Some(Some(nx)) if *nx < width => {
+                        src.path.push((*nx, y + 1));
+                        continue;
+                    }
+                    // anything leaving the window sideways has nothing left to land on
+                    Some(_) => return Step::Abyss,
+                    None => {}
+                }
+            }
+
+            filled.set(x, y);
+            src.path.pop();
+            self.settled += 1;
+            return Step::Settled(x as isize + self.x0, y);
+        }
+    }
+
+    // drops grains until no source can settle any more sand, returning the number settled
+    pub fn fill(&mut self) -> usize {
+        let start = self.settled;
+        let mut idle = 0;
+
+        while idle < self.sources.len() {
+            match self.step() {
+                Step::Settled(..) => idle = 0,
+                _ => idle += 1,
+            }
+        }
+
+        self.settled - start
+    }
+
+    // with a floor, every open cell reachable from a source ends up as sand, so the total can be
+    // counted row by row without simulating any grains
+    pub fn floor_fill_count(&self) -> Option<usize> {
+        self.floor?;
+
+        let stride = self.rock.stride;
+        let mask = self.rock.tail_mask();
+        let mut prev = vec![0u64; stride];
+        let mut total = 0;
+
+        for y in 0..self.rock.height {
+            let mut row: Vec<u64> = (0..stride).map(|w| {
+                let from_left = (prev[w] << 1) | if w > 0 { prev[w - 1] >> 63 } else { 0 };
+                let from_right = (prev[w] >> 1) | if w + 1 < stride { prev[w + 1] << 63 } else { 0 };
+                prev[w] | from_left | from_right
+            }).collect();
+
+            for src in self.sources.iter().filter(|s| s.y == y && s.x < self.rock.width) {
+                row[src.x / 64] |= 1 << (src.x % 64);
+            }
+
+            for (w, rock) in self.rock.row(y).iter().enumerate() {
+                row[w] &= !rock;
+            }
+            row[stride - 1] &= mask;
+
+            total += row.iter().map(|w| w.count_ones() as usize).sum::<usize>();
+            prev = row;
+        }
+
+        Some(total)
+    }
+}
+
+impl Render for Cave {
+    fn render(&self, canvas: &mut Canvas) {
+        let (width, height) = (self.rock.width, self.rock.height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let glyph = if self.rock.get(x, y) {
+                    '#'
+                } else if self.filled.get(x, y) {
+                    'o'
+                } else {
+                    '.'
+                };
+                canvas.put(x, y, glyph);
+            }
+        }
+
+        for (idx, src) in self.sources.iter().enumerate() {
+            for (x, y) in self.path(idx) {
+                canvas.put((x - self.x0) as usize, y, '~');
+            }
+            if !self.filled.get(src.x, src.y) {
+                canvas.put(src.x, src.y, '+');
+            }
+        }
+
+        if self.floor.is_some() {
+            for x in 0..width {
+                canvas.put(x, height, '#');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> HashSet<(usize, usize)> {
+        crate::parse(crate::TEST).unwrap()
+    }
+
+    // the window cell for absolute (x, y), if the cave covers it
+    fn cell(cave: &Cave, x: isize, y: usize) -> Option<(usize, usize)> {
+        let x = usize::try_from(x - cave.x0).ok()?;
+        (x < cave.rock.width && y < cave.rock.height).then_some((x, y))
+    }
+
+    fn is_sand(cave: &Cave, x: isize, y: usize) -> bool {
+        cell(cave, x, y).is_some_and(|(x, y)| cave.filled.get(x, y) && !cave.rock.get(x, y))
+    }
+
+    fn is_rock(cave: &Cave, x: isize, y: usize) -> bool {
+        cell(cave, x, y).is_some_and(|(x, y)| cave.rock.get(x, y))
+    }
+
+    #[test]
+    fn abyss() {
+        let mut cave = Cave::new(&sample(), &[DEFAULT_SOURCE], None);
+        assert_eq!(cave.fill(), 24);
+        assert_eq!(cave.step(), Step::Abyss);
+    }
+
+    #[test]
+    fn floor() {
+        let mut cave = Cave::new(&sample(), &[DEFAULT_SOURCE], Some(11));
+        assert_eq!(cave.floor_fill_count(), Some(93));
+        assert_eq!(cave.fill(), 93);
+        assert_eq!(cave.step(), Step::Blocked);
+    }
+
+    #[test]
+    fn first_grains() {
+        let mut cave = Cave::new(&sample(), &[DEFAULT_SOURCE], None);
+        assert_eq!(cave.step(), Step::Settled(500, 8));
+        assert_eq!(cave.step(), Step::Settled(499, 8));
+        assert_eq!(cave.step(), Step::Settled(501, 8));
+        assert!(is_sand(&cave, 499, 8));
+        assert!(is_rock(&cave, 500, 9));
+    }
+
+    #[test]
+    fn floor_past_origin() {
+        // the pile spreads two columns left of x = 0 before it reaches the floor
+        let mut cave = Cave::new(&HashSet::new(), &[(1, 0)], Some(4));
+        assert_eq!(cave.floor_fill_count(), Some(16));
+        assert_eq!(cave.fill(), 16);
+        assert_eq!(cave.step(), Step::Blocked);
+        assert!(is_sand(&cave, -2, 3));
+    }
+
+    #[test]
+    fn multiple_sources() {
+        let sources = [DEFAULT_SOURCE, (497, 2)];
+        let mut cave = Cave::new(&sample(), &sources, Some(11));
+        let expected = cave.floor_fill_count();
+        assert_eq!(Some(cave.fill()), expected);
+        assert!(expected.unwrap() > 93);
+    }
+
+    #[test]
+    fn render() {
+        let mut cave = Cave::new(&sample(), &[DEFAULT_SOURCE], None);
+        cave.fill();
+        assert_eq!(cave.text(), "\
+.......+....
+.......~....
+......~o....
+.....~ooo...
+....~#ooo##.
+...~o#ooo#..
+..~###ooo#..
+..~..oooo#..
+.~o.ooooo#..
+~#########..
+");
+    }
+}
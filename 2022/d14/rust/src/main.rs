@@ -1,8 +1,12 @@
 use std::collections::HashSet;
 use std::num::ParseIntError;
 
-const TEST: &'static str = include_str!("../../test");
-const INPUT: &'static str = include_str!("../../input");
+mod cave;
+
+use cave::{Cave, DEFAULT_SOURCE};
+
+const TEST: &str = include_str!("../../test");
+const INPUT: &str = include_str!("../../input");
 
 #[derive(Debug)]
 enum Day14Error {
@@ -15,60 +19,18 @@ impl From<ParseIntError> for Day14Error {
     }
 }
 
-struct SandUnit {
-    x: usize,
-    y: usize,
-}
-
-impl SandUnit {
-    fn new() -> Self {
-        Self { x: 500, y: 0 }
-    }
-
-    fn drop(&mut self, map: &HashSet<(usize,usize)>) -> bool {
-        if !map.contains( &(self.x, self.y + 1) ) {
-            self.y += 1;
-            true
-        } else if !map.contains( &(self.x - 1, self.y + 1) ) {
-            self.x -= 1;
-            self.y += 1;
-            true
-        } else if !map.contains( &(self.x + 1, self.y + 1) ) {
-            self.x += 1;
-            self.y += 1;
-            true
-        } else {
-            false
-        }
-    }
-
-    fn drop_floor(&mut self, map: &HashSet<(usize,usize)>, floor: usize) -> bool {
-        if self.y + 1 == floor {
-            false
-        } else {
-            self.drop(map)
-        }
-    }
-}
-
-impl From<SandUnit> for (usize,usize) {
-    fn from(sand: SandUnit) -> (usize,usize) {
-        (sand.x, sand.y)
-    }
-}
-
 fn parse(raw: &str) -> Result<HashSet<(usize, usize)>, Day14Error> {
     let mut map = HashSet::new();
 
     for line in raw.lines() {
         let corners = line.split(" -> ")
             .map(|raw_corner| {
-                let (fst, snd) = raw_corner.split_once(",").ok_or_else(|| Day14Error::ParseError(format!("failed to parse point")))?;
+                let (fst, snd) = raw_corner.split_once(",").ok_or_else(|| Day14Error::ParseError("failed to parse point".to_string()))?;
                 Ok( (fst.parse()?, snd.parse()?) )
             }).collect::<Result<Vec<(usize, usize)>, Day14Error>>()?;
 
         if corners.len() < 2 {
-            return Err(Day14Error::ParseError(format!("input line doesn't have at least two points; can't form any lines")));
+            return Err(Day14Error::ParseError("input line doesn't have at least two points; can't form any lines".to_string()));
         }
 
         for pair in corners.windows(2) {
@@ -95,44 +57,23 @@ fn parse(raw: &str) -> Result<HashSet<(usize, usize)>, Day14Error> {
 }
 
 fn part1(map: &HashSet<(usize, usize)>) -> usize {
-    let mut map = map.clone();
-    // unwrap: safe because hash set will never be empty
-    let bottom = map.iter().map(|(_,y)| *y).max().unwrap();
-
-    let mut sand = SandUnit::new();
-    let mut count = 0;
-
-    while sand.y < bottom {
-        if !sand.drop(&map) {
-            count += 1;
-            map.insert(sand.into());
-            sand = SandUnit::new();
-        }
-    }
-
-    count
+    Cave::new(map, &[DEFAULT_SOURCE], None).fill()
 }
 
 fn part2(map: &HashSet<(usize, usize)>) -> usize {
-    let mut map = map.clone();
     // unwrap: safe because hash set will never be empty
     let floor = map.iter().map(|(_,y)| *y).max().unwrap() + 2;
 
-    let mut sand = SandUnit::new();
-    let mut count = 0;
-
-    loop {
-        while sand.drop_floor(&map, floor) { }
-
-        count += 1;
+    let cave = Cave::new(map, &[DEFAULT_SOURCE], Some(floor));
+    // unwrap: the cave was built with a floor
+    cave.floor_fill_count().unwrap()
+}
 
-        if sand.y == 0 {
-            return count;
-        }
+fn part2_simulated(map: &HashSet<(usize, usize)>) -> usize {
+    // unwrap: safe because hash set will never be empty
+    let floor = map.iter().map(|(_,y)| *y).max().unwrap() + 2;
 
-        map.insert(sand.into());
-        sand = SandUnit::new();
-    }
+    Cave::new(map, &[DEFAULT_SOURCE], Some(floor)).fill()
 }
 
 fn main() -> Result<(), Day14Error> {
@@ -142,5 +83,6 @@ fn main() -> Result<(), Day14Error> {
     println!("part1 (actual): {}", part1(&input));
     println!("part2 (test): {}", part2(&test));
     println!("part2 (actual): {}", part2(&input));
+    println!("part2 (actual, simulated): {}", part2_simulated(&input));
     Ok( () )
 }
@@ -0,0 +1,148 @@
+// Manhattan-distance balls are diamonds in (x, y) but axis-aligned squares in the rotated
+// coordinates u = x + y, v = x - y, which turns their edges into horizontal and vertical lines.
+
+pub fn rotate((x, y): (isize, isize)) -> (isize, isize) {
+    (x + y, x - y)
+}
+
+// only points where u and v share a parity map back onto the integer grid
+pub fn unrotate((u, v): (isize, isize)) -> Option<(isize, isize)> {
+    if (u - v).rem_euclid(2) == 0 {
+        Some(((u + v) / 2, (u - v) / 2))
+    } else {
+        None
+    }
+}
+
+pub fn distance((ax, ay): (isize, isize), (bx, by): (isize, isize)) -> isize {
+    (ax - bx).abs() + (ay - by).abs()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diamond {
+    pub center: (isize, isize),
+    pub radius: isize,
+}
+
+impl Diamond {
+    pub fn new(center: (isize, isize), radius: isize) -> Self {
+        Diamond { center, radius }
+    }
+
+    pub fn contains(&self, p: (isize, isize)) -> bool {
+        distance(self.center, p) <= self.radius
+    }
+
+    // true for the ring of cells touching the diamond from the outside
+    pub fn just_outside(&self, p: (isize, isize)) -> bool {
+        distance(self.center, p) == self.radius + 1
+    }
+
+    // the inclusive span of x covered on row `y`
+    pub fn row_span(&self, y: isize) -> Option<(isize, isize)> {
+        let left = self.radius - (self.center.1 - y).abs();
+
+        if left >= 0 {
+            Some((self.center.0 - left, self.center.0 + left))
+        } else {
+            None
+        }
+    }
+
+    // the two u lines and two v lines that carry the just-outside ring
+    pub fn outer_lines(&self) -> ([isize; 2], [isize; 2]) {
+        let (u, v) = rotate(self.center);
+        let r = self.radius + 1;
+        ([u - r, u + r], [v - r, v + r])
+    }
+}
+
+// every point where an outer u line of one diamond crosses an outer v line of any diamond
+pub fn boundary_crossings(diamonds: &[Diamond]) -> Vec<(isize, isize)> {
+    let (us, vs): (Vec<_>, Vec<_>) = diamonds.iter().map(Diamond::outer_lines).unzip();
+
+    let mut points: Vec<_> = us.iter().flatten()
+        .flat_map(|&u| vs.iter().flatten().filter_map(move |&v| unrotate((u, v))))
+        .collect();
+
+    points.sort_unstable();
+    points.dedup();
+    points
+}
+
+// uncovered points where the just-outside rings of exactly `k` diamonds cross. only crossings
+// are searched, so `k` must be at least 2: a point on a single ring, or on a stretch where two
+// rings run alongside each other, is never reported
+pub fn ring_crossings(diamonds: &[Diamond], k: usize) -> Vec<(isize, isize)> {
+    assert!(k >= 2, "ring crossings involve at least two diamonds");
+
+    boundary_crossings(diamonds).into_iter()
+        .filter(|&p| !diamonds.iter().any(|d| d.contains(p)))
+        .filter(|&p| diamonds.iter().filter(|d| d.just_outside(p)).count() == k)
+        .collect()
+}
+
+// points in the square [lo, hi]^2 not covered by any diamond, considering only the spots that
+// are hemmed in by diamond edges or the square's own sides
+pub fn uncovered_in(diamonds: &[Diamond], lo: isize, hi: isize) -> Vec<(isize, isize)> {
+    let mut candidates = boundary_crossings(diamonds);
+
+    candidates.extend([(lo, lo), (lo, hi), (hi, lo), (hi, hi)]);
+    for d in diamonds {
+        let (us, vs) = d.outer_lines();
+        for edge in [lo, hi] {
+            for u in us {
+                candidates.extend([(edge, u - edge), (u - edge, edge)]);
+            }
+            for v in vs {
+                candidates.extend([(edge, edge - v), (v + edge, edge)]);
+            }
+        }
+    }
+
+    candidates.sort_unstable();
+    candidates.dedup();
+    candidates.retain(|&(x, y)| {
+        (lo..=hi).contains(&x) && (lo..=hi).contains(&y) && !diamonds.iter().any(|d| d.contains((x, y)))
+    });
+    candidates
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotation_round_trip() {
+        for p in [(0, 0), (3, -7), (-2, 5), (10, 10)] {
+            assert_eq!(unrotate(rotate(p)), Some(p));
+        }
+        assert_eq!(unrotate((1, 0)), None);
+    }
+
+    #[test]
+    fn row_span() {
+        let d = Diamond::new((8, 7), 9);
+        assert_eq!(d.row_span(10), Some((2, 14)));
+        assert_eq!(d.row_span(16), Some((8, 8)));
+        assert_eq!(d.row_span(17), None);
+    }
+
+    #[test]
+    fn hole_between_four() {
+        let diamonds = [
+            Diamond::new((0, 2), 1),
+            Diamond::new((4, 2), 1),
+            Diamond::new((2, 0), 1),
+            Diamond::new((2, 4), 1),
+        ];
+        assert_eq!(ring_crossings(&diamonds, 4), vec![(2, 2)]);
+        assert!(uncovered_in(&diamonds, 1, 3).contains(&(2, 2)));
+    }
+
+    #[test]
+    fn box_corner() {
+        let diamonds = [Diamond::new((0, 0), 3)];
+        assert_eq!(uncovered_in(&diamonds, 0, 2), vec![(2, 2)]);
+    }
+}
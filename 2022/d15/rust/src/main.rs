@@ -2,6 +2,8 @@ use std::collections::HashSet;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
+use rust::diamond::{self, Diamond};
+
 const TEST: &'static str = include_str!("../../test");
 const INPUT: &'static str = include_str!("../../input");
 
@@ -100,20 +102,14 @@ struct Sensor {
 }
 
 impl Sensor {
-    fn range_at_line(&self, line: isize) -> Option<Range> {
-        let dx = (self.loc.0 - self.nearest_beacon.0).abs();
-        let dy = (self.loc.1 - self.nearest_beacon.1).abs();
-
-        let dl = (self.loc.1 - line).abs();
+    fn diamond(&self) -> Diamond {
+        let loc = (self.loc.0, self.loc.1);
+        let beacon = (self.nearest_beacon.0, self.nearest_beacon.1);
+        Diamond::new(loc, diamond::distance(loc, beacon))
+    }
 
-        if dl <= dx + dy {
-            Some(Range::new(
-                    self.loc.0 - (dx + dy) + dl,
-                    self.loc.0 + (dx + dy) - dl,
-            ))
-        } else {
-            None
-        }
+    fn range_at_line(&self, line: isize) -> Option<Range> {
+        self.diamond().row_span(line).map(|(start, end)| Range::new(start, end))
     }
 }
 
@@ -182,7 +178,24 @@ fn tuning_frequency(x: isize, y: isize) -> isize {
     (x * 4000000) + y
 }
 
-fn part2(sensors: &[Sensor], search_space: isize) -> isize {
+// None unless the search space holds exactly one open spot for the beacon
+fn part2(sensors: &[Sensor], search_space: isize) -> Option<isize> {
+    let diamonds = sensors.iter().map(Sensor::diamond).collect::<Vec<_>>();
+
+    match diamond::uncovered_in(&diamonds, 0, search_space)[..] {
+        [(x, y)] => Some(tuning_frequency(x, y)),
+        _ => None,
+    }
+}
+
+fn report(label: &str, frequency: Option<isize>) {
+    match frequency {
+        Some(frequency) => println!("{}: {}", label, frequency),
+        None => println!("{}: no single open spot for the beacon", label),
+    }
+}
+
+fn part2_scan(sensors: &[Sensor], search_space: isize) -> isize {
     for line in 0..=search_space {
         let mut ranges = sensors.iter().filter_map(|s| {
             s.range_at_line(line as isize)
@@ -216,8 +229,9 @@ fn main() -> Result<(), Day15Error> {
     let input = parse(INPUT)?;
     println!("part1 (test): {}", part1(&test, 10));
     println!("part1 (actual): {}", part1(&input, 2000000));
-    println!("part2 (test): {}", part2(&test, 20));
-    println!("part2 (actual): {}", part2(&input, 4000000));
+    report("part2 (test)", part2(&test, 20));
+    println!("part2 (test, scan): {}", part2_scan(&test, 20));
+    report("part2 (actual)", part2(&input, 4000000));
     Ok( () )
 }
 
@@ -231,6 +245,35 @@ mod test {
 
         let range = sensor.range_at_line(10);
 
-        assert_eq!(range, Range::new(2, 14));
+        assert_eq!(range, Some(Range::new(2, 14)));
+    }
+
+    #[test]
+    fn part2_matches_scan() {
+        let sensors = parse(TEST).unwrap();
+
+        assert_eq!(part2(&sensors, 20), Some(56000011));
+        assert_eq!(part2_scan(&sensors, 20), 56000011);
+    }
+
+    #[test]
+    fn part2_no_single_spot() {
+        let sensors = parse(TEST).unwrap();
+
+        assert_eq!(part2(&sensors[..1], 20), None);
+        assert_eq!(part2(&sensors, 0), None);
+    }
+
+    // the actual beacon sits in an interior gap one cell wide, just outside four diamonds at once
+    #[test]
+    fn part2_ringed() {
+        let sensors = parse(INPUT).unwrap();
+        let diamonds = sensors.iter().map(Sensor::diamond).collect::<Vec<_>>();
+
+        let ringed = diamond::ring_crossings(&diamonds, 4).into_iter()
+            .find(|&(x, y)| (0..=4000000).contains(&x) && (0..=4000000).contains(&y))
+            .map(|(x, y)| tuning_frequency(x, y));
+
+        assert_eq!(ringed, part2(&sensors, 4000000));
     }
 }
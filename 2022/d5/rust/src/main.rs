@@ -1,191 +1,72 @@
-use std::str::FromStr;
 use std::fmt;
 
+use render::Render;
+use rust::stacks::{Command, CrateMover9000, CrateMover9001, CrateState, Crane, StackError};
+use sections::{InputError, SectionError, Sections};
+
 const TEST_DATA: &'static str = include_str!("../../test");
 const INPUT_DATA: &'static str = include_str!("../../input");
 
 #[derive(Debug)]
 enum Day5Error {
     ParseError(String),
+    // the 1-based number of the move that failed
+    CraneError(usize, StackError),
 }
 
-impl From<std::num::ParseIntError> for Day5Error {
-    fn from(value: std::num::ParseIntError) -> Self {
-        Self::ParseError(value.to_string())
-    }
-}
-
-#[derive(Clone)]
-struct CrateState {
-    stacks: Vec<Vec<char>>,
-}
-
-impl CrateState {
-    fn perform_part1(&mut self, cmd: &Command) {
-        let Command { num, from, to } = cmd;
-
-        for _ in 0..*num {
-            let ch = self.stacks[*from - 1].pop().expect("tried to grab a crate from an empty stack");
-            self.stacks[*to - 1].push(ch);
-        }
-    }
-
-    fn perform_part2(&mut self, cmd: &Command) {
-        let Command { num, from, to } = cmd;
-
-        let from = &mut self.stacks[*from - 1];
-        let mut chs = from.split_off(from.len() - num);
-        self.stacks[*to - 1].append(&mut chs);
-    }
-
-    fn read_top(&self) -> String {
-        self.stacks.iter().map(|s| *s.last().expect("tried to read top of empty stack")).collect()
-    }
-}
-
-impl fmt::Display for CrateState {
+impl fmt::Display for Day5Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // unwrap: safe because the state will fail to parse if it has no crates.
-        let height = self.stacks.iter().map(|v| v.len() - 1).max().unwrap();
-
-        for h in (0..=height).rev() {
-            for stack in self.stacks.iter() {
-                if let Some(ch) = stack.get(h) {
-                    write!(f, "[{}] ", ch)?;
-                } else {
-                    write!(f, "    ")?;
-                }
-            }
-            writeln!(f, "")?;
+        match self {
+            Day5Error::ParseError(msg) => write!(f, "parse error: {}", msg),
+            Day5Error::CraneError(step, e) => write!(f, "move {}: {}", step, e),
         }
-
-        for (idx, _) in self.stacks.iter().enumerate() {
-            write!(f, " {}  ", idx + 1)?;
-        }
-
-        Ok( () )
     }
 }
 
-impl FromStr for CrateState {
-    type Err = Day5Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() == 0 {
-            return Err(Day5Error::ParseError(format!("No starting state found")));
-        }
-
-        let mut state = CrateState {
-            stacks: vec![],
-        };
-
-        let lines = s.lines();
-
-        for line in lines {
-            let mut idx = 0;
-            while let Some(ch) = line.chars().nth((idx * 4) + 1) {
-                match ch {
-                    'A'..='Z' => {
-                        while state.stacks.len() < idx + 1 {
-                            state.stacks.push(vec![]);
-                        }
-
-                        // unwrap: safe because we ensure proper length above
-                        let stack = state.stacks.get_mut(idx).unwrap();
-                        stack.insert(0, ch);
-                    }
-                    ' ' => {},
-                    _ => return Err(Day5Error::ParseError(format!("unexpected character in stack: {} at idx: {}", ch, idx))),
-                }
-
-                idx += 1;
-            }
-        }
-
-        Ok(state)
+impl From<SectionError> for Day5Error {
+    fn from(error: SectionError) -> Self {
+        Self::ParseError(error.to_string())
     }
 }
 
-struct Command {
-    num: usize,
-    from: usize,
-    to: usize,
-}
-
-impl FromStr for Command {
-    type Err = Day5Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn expect(actual: Option<&str>, expected: &str) -> Result<(), Day5Error> {
-            if let Some(a) = actual {
-                if a == expected {
-                    Ok( () )
-                } else {
-                    Err(Day5Error::ParseError(format!("Expected '{}', but got: {}", expected, a)))
-                }
-            } else {
-                Err(Day5Error::ParseError(format!("Expected '{}', but the line was terminated", expected)))
-            }
-        }
-
-        let mut words = s.split_whitespace();
-
-        expect(words.next(), "move")?;
-        let num = words.next().ok_or(Day5Error::ParseError(format!("No number of crates to move found")))?
-            .parse()?;
-        expect(words.next(), "from")?;
-        let from = words.next().ok_or(Day5Error::ParseError(format!("No stack to move from")))?
-            .parse()?;
-        expect(words.next(), "to")?;
-        let to = words.next().ok_or(Day5Error::ParseError(format!("No stack to move to")))?
-            .parse()?;
-
-        Ok(Command { num, from, to })
+impl From<InputError<StackError>> for Day5Error {
+    fn from(error: InputError<StackError>) -> Self {
+        Self::ParseError(error.to_string())
     }
 }
 
 fn parse(raw: &str) -> Result<(CrateState, Vec<Command>), Day5Error> {
-    let mut lines = raw.lines();
-
-    let start_state = {
-        let state_lines = lines.by_ref().take_while(|line| line.len() > 0).collect::<Vec<_>>();
-        state_lines[0..state_lines.len() - 1].join("\n").parse()?
-    };
+    let sections = Sections::new(raw).named(&["stacks", "moves"])?;
 
-    let mut commands = vec![];
-    for raw_command in lines {
-        commands.push(raw_command.parse()?);
-    }
+    let start_state = sections.get("stacks")?.parse_with(CrateState::parse_drawing)?;
+    let commands = sections.get("moves")?.parse_lines(str::parse)?;
 
     Ok( (start_state, commands) )
 }
 
-fn part1(start_state: &CrateState, commands: &[Command]) -> String {
-    let mut state = Clone::clone(start_state);
-
-    for command in commands {
-        state.perform_part1(command);
-    }
+fn run(start_state: &CrateState, commands: &[Command], crane: &dyn Crane, steps: bool) -> Result<String, Day5Error> {
+    let mut state = start_state.clone();
 
-    state.read_top()
-}
-
-fn part2(start_state: &CrateState, commands: &[Command]) -> String {
-    let mut state = Clone::clone(start_state);
-
-    for command in commands {
-        state.perform_part2(command);
+    if steps {
+        for (idx, command) in commands.iter().enumerate() {
+            state.apply(command, crane).map_err(|e| Day5Error::CraneError(idx + 1, e))?;
+            println!("{}\n{}", command, state.text());
+        }
+    } else {
+        state.run(commands, crane).map_err(|(idx, e)| Day5Error::CraneError(idx + 1, e))?;
     }
 
-    state.read_top()
+    Ok(state.read_top())
 }
 
 fn main() -> Result<(), Day5Error> {
+    let steps = std::env::args().any(|a| a == "--steps");
+
     let (test_start_state, test_commands) = parse(TEST_DATA)?;
     let (input_start_state, input_commands) = parse(INPUT_DATA)?;
-    println!("part1 (test): {:?}", part1(&test_start_state, &test_commands));
-    println!("part1 (actual): {:?}", part1(&input_start_state, &input_commands));
-    println!("part2 (test): {:?}", part2(&test_start_state, &test_commands));
-    println!("part2 (actual): {:?}", part2(&input_start_state, &input_commands));
+    println!("part1 (test): {:?}", run(&test_start_state, &test_commands, &CrateMover9000, steps)?);
+    println!("part1 (actual): {:?}", run(&input_start_state, &input_commands, &CrateMover9000, false)?);
+    println!("part2 (test): {:?}", run(&test_start_state, &test_commands, &CrateMover9001, steps)?);
+    println!("part2 (actual): {:?}", run(&input_start_state, &input_commands, &CrateMover9001, false)?);
     Ok( () )
 }
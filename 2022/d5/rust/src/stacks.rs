@@ -0,0 +1,404 @@
+use std::fmt;
+use std::str::FromStr;
+
+use render::{Canvas, Render};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackError {
+    ParseError(String),
+    // stacks are numbered from 1
+    NoSuchStack(usize),
+    // a move wanting more crates than its stack holds
+    Underflow { stack: usize, needed: usize, had: usize },
+}
+
+impl fmt::Display for StackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StackError::ParseError(msg) => write!(f, "parse error: {}", msg),
+            StackError::NoSuchStack(stack) => write!(f, "there's no stack {}", stack),
+            StackError::Underflow { stack, needed, had } => {
+                write!(f, "tried to move {} crates from stack {}, which only has {}", needed, stack, had)
+            }
+        }
+    }
+}
+
+impl From<std::num::ParseIntError> for StackError {
+    fn from(value: std::num::ParseIntError) -> Self {
+        Self::ParseError(value.to_string())
+    }
+}
+
+// how a crane sets down the crates it lifts in one move
+pub trait Crane {
+    // `lifted` comes in stack order, bottom first, and is left in the order the crates land
+    fn arrange(&self, lifted: &mut [String]);
+}
+
+// moves one crate at a time, so the lifted crates land upside down
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn arrange(&self, lifted: &mut [String]) {
+        lifted.reverse();
+    }
+}
+
+// moves every crate at once, keeping their order
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn arrange(&self, _lifted: &mut [String]) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Command {
+    pub num: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.num, self.from, self.to)
+    }
+}
+
+impl FromStr for Command {
+    type Err = StackError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn expect(actual: Option<&str>, expected: &str) -> Result<(), StackError> {
+            if let Some(a) = actual {
+                if a == expected {
+                    Ok( () )
+                } else {
+                    Err(StackError::ParseError(format!("Expected '{}', but got: {}", expected, a)))
+                }
+            } else {
+                Err(StackError::ParseError(format!("Expected '{}', but the line was terminated", expected)))
+            }
+        }
+
+        let mut words = s.split_whitespace();
+
+        expect(words.next(), "move")?;
+        let num = words.next().ok_or_else(|| StackError::ParseError("No number of crates to move found".to_string()))?
+            .parse()?;
+        expect(words.next(), "from")?;
+        let from = words.next().ok_or_else(|| StackError::ParseError("No stack to move from".to_string()))?
+            .parse()?;
+        expect(words.next(), "to")?;
+        let to = words.next().ok_or_else(|| StackError::ParseError("No stack to move to".to_string()))?
+            .parse()?;
+
+        if let Some(extra) = words.next() {
+            return Err(StackError::ParseError(format!("unexpected {:?} after the command", extra)));
+        }
+
+        Ok(Command { num, from, to })
+    }
+}
+
+// a move that's been made, with enough to take it back
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Applied {
+    command: Command,
+    // the crates as they were on the `from` stack, bottom first
+    lifted: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateState {
+    // bottom crate first
+    stacks: Vec<Vec<String>>,
+    log: Vec<Applied>,
+}
+
+impl CrateState {
+    pub fn new(stacks: Vec<Vec<String>>) -> Self {
+        CrateState { stacks, log: vec![] }
+    }
+
+    pub fn stacks(&self) -> &[Vec<String>] {
+        &self.stacks
+    }
+
+    fn index(&self, stack: usize) -> Result<usize, StackError> {
+        if stack == 0 || stack > self.stacks.len() {
+            Err(StackError::NoSuchStack(stack))
+        } else {
+            Ok(stack - 1)
+        }
+    }
+
+    // checks the command could run right now without changing anything
+    pub fn validate(&self, command: &Command) -> Result<(), StackError> {
+        let from = self.index(command.from)?;
+        self.index(command.to)?;
+
+        let had = self.stacks[from].len();
+        if command.num > had {
+            return Err(StackError::Underflow { stack: command.from, needed: command.num, had });
+        }
+        Ok(())
+    }
+
+    // runs one command, leaving the state untouched if it's invalid
+    pub fn apply(&mut self, command: &Command, crane: &dyn Crane) -> Result<(), StackError> {
+        self.validate(command)?;
+        let (from, to) = (command.from - 1, command.to - 1);
+
+        let at = self.stacks[from].len() - command.num;
+        let lifted = self.stacks[from].split_off(at);
+        let mut landed = lifted.clone();
+        crane.arrange(&mut landed);
+        self.stacks[to].extend(landed);
+
+        self.log.push(Applied { command: *command, lifted });
+        Ok(())
+    }
+
+    // runs commands in order, stopping at the first invalid one and reporting its index; the
+    // commands before it stay applied
+    pub fn run(&mut self, commands: &[Command], crane: &dyn Crane) -> Result<(), (usize, StackError)> {
+        for (idx, command) in commands.iter().enumerate() {
+            self.apply(command, crane).map_err(|e| (idx, e))?;
+        }
+        Ok(())
+    }
+
+    // takes back the last move, returning it
+    pub fn undo(&mut self) -> Option<Command> {
+        let Applied { command, lifted } = self.log.pop()?;
+        let to = &mut self.stacks[command.to - 1];
+        to.truncate(to.len() - command.num);
+        self.stacks[command.from - 1].extend(lifted);
+        Some(command)
+    }
+
+    // takes back up to n moves, returning how many it took back
+    pub fn rewind(&mut self, n: usize) -> usize {
+        (0..n).take_while(|_| self.undo().is_some()).count()
+    }
+
+    // the moves made so far, oldest first
+    pub fn history(&self) -> impl Iterator<Item = &Command> {
+        self.log.iter().map(|a| &a.command)
+    }
+
+    // the top crate of each stack, with None for empty stacks
+    pub fn tops(&self) -> Vec<Option<&str>> {
+        self.stacks.iter().map(|s| s.last().map(|c| c.as_str())).collect()
+    }
+
+    // the top crates' labels run together, skipping empty stacks
+    pub fn read_top(&self) -> String {
+        self.tops().into_iter().flatten().collect()
+    }
+
+    // parses a drawing, including the line numbering the stacks under it. crates belong to
+    // whichever stack's number they sit over, so labels can be any width and stack numbers any
+    // length. errors come with the line of the drawing they're on
+    pub fn parse_drawing(lines: &[&str]) -> Result<Self, (usize, StackError)> {
+        let err = |line: usize, msg: String| (line, StackError::ParseError(msg));
+
+        let (footer, rows) = lines.split_last().ok_or_else(|| err(0, "No starting state found".to_string()))?;
+        let footer_line = rows.len();
+
+        // where each stack's number sits in the footer, by char column
+        let mut columns = vec![];
+        let chars: Vec<char> = footer.chars().collect();
+        let mut col = 0;
+        while col < chars.len() {
+            if chars[col] == ' ' {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            while col < chars.len() && chars[col] != ' ' {
+                col += 1;
+            }
+            let raw: String = chars[start..col].iter().collect();
+            let number: usize = raw.parse().map_err(|_| err(footer_line, format!("bad stack number {:?}", raw)))?;
+            if number != columns.len() + 1 {
+                return Err(err(footer_line, format!("expected stack {} but found {}", columns.len() + 1, number)));
+            }
+            columns.push((start, col - 1));
+        }
+
+        let mut stacks: Vec<Vec<String>> = vec![vec![]; columns.len()];
+        // set once a stack has a gap, so a crate above it would be floating
+        let mut topped = vec![false; columns.len()];
+
+        for (line, row) in rows.iter().enumerate().rev() {
+            let mut filled = vec![false; columns.len()];
+            let chars: Vec<char> = row.chars().collect();
+            let mut col = 0;
+
+            while col < chars.len() {
+                match chars[col] {
+                    ' ' => col += 1,
+                    '[' => {
+                        let len = chars[col..].iter().position(|&c| c == ']')
+                            .ok_or_else(|| err(line, format!("unclosed crate at column {}", col + 1)))?;
+                        let label: String = chars[col + 1..col + len].iter().collect();
+                        if label.is_empty() || label.contains('[') {
+                            return Err(err(line, format!("bad crate label {:?} at column {}", label, col + 1)));
+                        }
+
+                        let (start, end) = (col, col + len);
+                        let mut over = columns.iter().enumerate().filter(|(_, &(s, e))| s <= end && start <= e).map(|(i, _)| i);
+                        let stack = match (over.next(), over.next()) {
+                            (Some(stack), None) => stack,
+                            _ => return Err(err(line, format!("crate {:?} isn't over exactly one stack", label))),
+                        };
+
+                        if topped[stack] {
+                            return Err(err(line, format!("crate {:?} on stack {} is floating", label, stack + 1)));
+                        }
+                        if filled[stack] {
+                            return Err(err(line, format!("two crates on stack {} in one row", stack + 1)));
+                        }
+                        filled[stack] = true;
+                        stacks[stack].push(label);
+                        col = end + 1;
+                    }
+                    c => return Err(err(line, format!("unexpected character {:?} at column {}", c, col + 1))),
+                }
+            }
+
+            for (t, f) in topped.iter_mut().zip(filled) {
+                *t |= !f;
+            }
+        }
+
+        Ok(CrateState::new(stacks))
+    }
+}
+
+impl Render for CrateState {
+    fn render(&self, canvas: &mut Canvas) {
+        let height = self.stacks.iter().map(|v| v.len()).max().unwrap_or(0);
+        // wide enough for the widest label and the biggest stack number
+        let label = self.stacks.iter().flatten().map(|c| c.chars().count()).max().unwrap_or(1);
+        let stride = label.max(self.stacks.len().to_string().len()) + 3;
+
+        for (idx, stack) in self.stacks.iter().enumerate() {
+            for (h, label) in stack.iter().enumerate() {
+                canvas.write(idx * stride, height - 1 - h, &format!("[{}]", label));
+            }
+            canvas.write(idx * stride, height, &format!(" {} ", idx + 1));
+        }
+    }
+}
+
+impl FromStr for CrateState {
+    type Err = StackError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().filter(|l| !l.trim().is_empty()).collect();
+        CrateState::parse_drawing(&lines).map_err(|(_, e)| e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SAMPLE: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+
+    fn moves(commands: &str) -> Vec<Command> {
+        commands.lines().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn cranes() {
+        let commands = moves("move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2");
+        let start: CrateState = SAMPLE.parse().unwrap();
+
+        let mut state = start.clone();
+        state.run(&commands, &CrateMover9000).unwrap();
+        assert_eq!(state.read_top(), "CMZ");
+
+        let mut state = start.clone();
+        state.run(&commands, &CrateMover9001).unwrap();
+        assert_eq!(state.read_top(), "MCD");
+
+        // a custom crane that can only carry two crates at a time
+        struct Pairs;
+        impl Crane for Pairs {
+            fn arrange(&self, lifted: &mut [String]) {
+                lifted.reverse();
+                for pair in lifted.chunks_mut(2) {
+                    pair.reverse();
+                }
+            }
+        }
+        let mut state: CrateState = "[A]\n[B]\n[C]\n 1   2 \n".parse().unwrap();
+        state.apply(&"move 3 from 1 to 2".parse().unwrap(), &Pairs).unwrap();
+        assert_eq!(state.stacks()[1], ["B", "A", "C"]);
+    }
+
+    #[test]
+    fn undo() {
+        let commands = moves("move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1");
+        let start: CrateState = SAMPLE.parse().unwrap();
+        let mut state = start.clone();
+        state.run(&commands, &CrateMover9000).unwrap();
+        assert_eq!(state.history().count(), 3);
+
+        assert_eq!(state.undo(), Some(commands[2]));
+        assert_eq!(state.rewind(5), 2);
+        assert_eq!(state.undo(), None);
+        assert_eq!(state.stacks(), start.stacks());
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let mut state: CrateState = SAMPLE.parse().unwrap();
+        let before = state.clone();
+
+        let err = state.apply(&"move 4 from 1 to 2".parse().unwrap(), &CrateMover9001);
+        assert_eq!(err, Err(StackError::Underflow { stack: 1, needed: 4, had: 2 }));
+        assert_eq!(state.apply(&"move 1 from 0 to 2".parse().unwrap(), &CrateMover9001), Err(StackError::NoSuchStack(0)));
+        assert_eq!(state.apply(&"move 1 from 1 to 4".parse().unwrap(), &CrateMover9001), Err(StackError::NoSuchStack(4)));
+        assert_eq!(state, before);
+
+        let commands = moves("move 1 from 3 to 1\nmove 1 from 3 to 1");
+        assert!(matches!(state.run(&commands, &CrateMover9000), Err((1, StackError::Underflow { .. }))));
+        assert_eq!(state.tops(), [Some("P"), Some("D"), None]);
+        assert_eq!(state.read_top(), "PD");
+    }
+
+    #[test]
+    fn wide_drawings() {
+        let drawing = "[AB]       [X]\n[CD] [E]  [YZ]\n 1    2    3 ";
+        let state: CrateState = drawing.parse().unwrap();
+        assert_eq!(state.stacks(), [vec!["CD", "AB"], vec!["E"], vec!["YZ", "X"]]);
+        assert_eq!(state.read_top(), "ABEX");
+
+        // eleven stacks five columns apart, with a two-character crate on the last
+        let footer: String = (1..=11).map(|n| format!(" {:<4}", n)).collect();
+        let bottom: String = (0..11).map(|i| format!("[{:02}] ", i)).collect();
+        let top = " ".repeat(50) + "[Kx]";
+        let state: CrateState = format!("{}\n{}\n{}", top, bottom.trim_end(), footer).parse().unwrap();
+
+        assert_eq!(state.stacks().len(), 11);
+        assert_eq!(state.stacks()[10], ["10", "Kx"]);
+        assert_eq!(state.stacks()[3], ["03"]);
+    }
+
+    #[test]
+    fn bad_drawings() {
+        let floating = CrateState::parse_drawing(&["[A]", "   ", " 1 "]);
+        assert!(matches!(floating, Err((0, StackError::ParseError(_)))));
+
+        let unclosed = CrateState::parse_drawing(&["[A] [B", " 1   2 "]);
+        assert!(matches!(unclosed, Err((0, StackError::ParseError(_)))));
+
+        let bad_footer = CrateState::parse_drawing(&["[A]", " 2 "]);
+        assert!(matches!(bad_footer, Err((1, StackError::ParseError(_)))));
+    }
+}
@@ -1,10 +1,13 @@
-use std::collections::HashSet;
 use std::num::ParseIntError;
 use std::str::FromStr;
 
-const TEST: &'static str = include_str!("../../test");
-const TEST2: &'static str = include_str!("../../test2");
-const INPUT: &'static str = include_str!("../../input");
+use rust::rope::Rope;
+#[cfg(test)]
+use rust::rope::{FollowRule, Position};
+
+const TEST: &str = include_str!("../../test");
+const TEST2: &str = include_str!("../../test2");
+const INPUT: &str = include_str!("../../input");
 
 #[derive(Debug)]
 enum Day9Error {
@@ -23,6 +26,10 @@ enum Direction {
     Left,
     Up,
     Down,
+    UpRight,
+    UpLeft,
+    DownRight,
+    DownLeft,
 }
 
 impl Direction {
@@ -32,6 +39,10 @@ impl Direction {
             Direction::Left  => (-1, 0),
             Direction::Up    => (0, 1),
             Direction::Down  => (0, -1),
+            Direction::UpRight   => (1, 1),
+            Direction::UpLeft    => (-1, 1),
+            Direction::DownRight => (1, -1),
+            Direction::DownLeft  => (-1, -1),
         }
     }
 }
@@ -45,6 +56,10 @@ impl FromStr for Direction {
             "L" => Ok(Direction::Left),
             "U" => Ok(Direction::Up),
             "D" => Ok(Direction::Down),
+            "UR" => Ok(Direction::UpRight),
+            "UL" => Ok(Direction::UpLeft),
+            "DR" => Ok(Direction::DownRight),
+            "DL" => Ok(Direction::DownLeft),
             x => Err(Day9Error::ParseError(format!("unknown direction: {}", x))),
         }
     }
@@ -61,7 +76,7 @@ impl FromStr for Command {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (raw_dir, raw_dist) = s.split_once(" ")
-            .ok_or_else(|| Day9Error::ParseError(format!("failed to split line on space character")))?;
+            .ok_or_else(|| Day9Error::ParseError("failed to split line on space character".to_string()))?;
 
         let dir = raw_dir.parse()?;
         let dist = raw_dist.parse()?;
@@ -72,43 +87,6 @@ impl FromStr for Command {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
-struct Position {
-    x: isize,
-    y: isize,
-}
-
-impl Position {
-    fn new() -> Self {
-        Position {
-            x: 0,
-            y: 0,
-        }
-    }
-
-    fn step(&mut self, d: &Direction) {
-        let (dx, dy) = d.delta();
-        self.x += dx;
-        self.y += dy;
-    }
-
-    fn chase(&mut self, followee: &Position) {
-        let (dx, dy) = (followee.x - self.x, followee.y - self.y);
-
-        if dx > 1 || dx < -1 {
-            self.x += dx / dx.abs();
-            if dy != 0 {
-                self.y += dy / dy.abs();
-            }
-        } else if dy > 1 || dy < -1 {
-            self.y += dy / dy.abs();
-            if dx != 0 {
-                self.x += dx / dx.abs();
-            }
-        }
-    }
-}
-
 fn parse(raw: &str) -> Result<Vec<Command>, Day9Error> {
     // requires nightly
     // raw.lines().map(|m| m.parse()).try_collect()
@@ -122,64 +100,32 @@ fn parse(raw: &str) -> Result<Vec<Command>, Day9Error> {
     Ok(commands)
 }
 
-/*
-fn display_locations(rope: &[Position]) {
-    let rx = rope.iter().map(|p| p.x).min().unwrap().min(0)..=rope.iter().map(|p| p.x).max().unwrap().max(0);
-    let ry = rope.iter().map(|p| p.y).min().unwrap().min(0)..=rope.iter().map(|p| p.y).max().unwrap().max(0);
-
-    for y in ry.rev() {
-        for x in rx.clone() {
-            if let Some(idx) = rope.iter().position(|p| p.x == x && p.y == y) {
-                if idx == 0 {
-                    print!("H");
-                } else {
-                    print!("{}", idx);
-                }
-            } else if x == 0 && y == 0 {
-                print!("s");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
-    }
-}
-*/
-
-fn pull(commands: &[Command], rope_length: usize) -> usize {
-    let mut positions = vec![Position::new(); rope_length];
-
-    let mut tail_visited = HashSet::new();
+fn pull(commands: &[Command], rope_length: usize) -> Rope {
+    let mut rope = Rope::new(rope_length);
 
     for command in commands {
-        for _ in 0..command.dist {
-            positions[0].step(&command.dir);
-            for pos in 1..rope_length {
-                let followee = &positions[pos - 1].clone();
-                positions[pos].chase(&followee);
-            }
-            tail_visited.insert(positions[rope_length - 1].clone());
-        }
-
-        /*
-        println!("{:?}", command);
-        display_locations(&positions);
-        println!("visited: {}", tail_visited.len());
-        println!();
-        */
+        let (dx, dy) = command.dir.delta();
+        let dist = command.dist as isize;
+        rope.move_head(dx * dist, dy * dist);
     }
 
-    tail_visited.len()
+    rope
 }
 
 fn main() -> Result<(), Day9Error> {
     let test = parse(TEST)?;
     let test2 = parse(TEST2)?;
     let input = parse(INPUT)?;
-    println!("part1 (test): {}", pull(&test, 2));
-    println!("part1 (actual): {}", pull(&input, 2));
-    println!("part2 (test): {}", pull(&test2, 10));
-    println!("part2 (actual): {}", pull(&input, 10));
+    println!("part1 (test): {}", pull(&test, 2).visited(1).len());
+    println!("part1 (actual): {}", pull(&input, 2).visited(1).len());
+    println!("part2 (test): {}", pull(&test2, 10).visited(9).len());
+
+    let rope = pull(&input, 10);
+    println!("part2 (actual): {}", rope.visited(9).len());
+    match rope.first_revisit(9) {
+        Some(step) => println!("tail first revisit (actual): step {} of {}", step, rope.steps()),
+        None => println!("tail first revisit (actual): never, in {} steps", rope.steps()),
+    }
     Ok( () )
 }
 
@@ -193,8 +139,8 @@ mod test {
             fn $name() {
                 let mut head = $hs;
                 let mut tail = $ts;
-                head.step(&$dir);
-                tail.chase(&head);
+                head.step($dir.delta());
+                tail.chase(&head, FollowRule::King, 0);
 
                 assert_eq!(head, $he, "head");
                 assert_eq!(tail, $te, "tail");
@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+
+use render::{Canvas, Render};
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
+pub struct Position {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Position {
+    pub fn new() -> Self {
+        Position {
+            x: 0,
+            y: 0,
+        }
+    }
+
+    pub fn step(&mut self, (dx, dy): (isize, isize)) {
+        self.x += dx;
+        self.y += dy;
+    }
+
+    pub fn chase(&mut self, followee: &Position, rule: FollowRule, slack: usize) -> bool {
+        let delta = rule.delta(self, followee, slack);
+        self.step(delta);
+        delta != (0, 0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowRule {
+    // a knot may close the gap on both axes at once, like a chess king
+    King,
+    // a knot may only move along one axis per step
+    Orthogonal,
+}
+
+impl FollowRule {
+    fn distance(&self, dx: isize, dy: isize) -> isize {
+        match self {
+            FollowRule::King => dx.abs().max(dy.abs()),
+            FollowRule::Orthogonal => dx.abs() + dy.abs(),
+        }
+    }
+
+    pub fn delta(&self, follower: &Position, followee: &Position, slack: usize) -> (isize, isize) {
+        let (dx, dy) = (followee.x - follower.x, followee.y - follower.y);
+
+        if self.distance(dx, dy) <= 1 + slack as isize {
+            return (0, 0);
+        }
+
+        match self {
+            FollowRule::King => (dx.signum(), dy.signum()),
+            FollowRule::Orthogonal if dx.abs() >= dy.abs() => (dx.signum(), 0),
+            FollowRule::Orthogonal => (0, dy.signum()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Rope {
+    knots: Vec<Position>,
+    visited: Vec<HashSet<Position>>,
+    first_revisit: Vec<Option<usize>>,
+    rule: FollowRule,
+    slack: usize,
+    steps: usize,
+}
+
+impl Rope {
+    pub fn new(length: usize) -> Self {
+        assert!(length > 0, "a rope needs at least one knot");
+
+        let knots = vec![Position::new(); length];
+        let visited = knots.iter()
+            .map(|k| HashSet::from([k.clone()]))
+            .collect();
+
+        Rope {
+            knots,
+            visited,
+            first_revisit: vec![None; length],
+            rule: FollowRule::King,
+            slack: 0,
+            steps: 0,
+        }
+    }
+
+    pub fn with_rule(mut self, rule: FollowRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    // how far past touching a knot may drift before it starts to follow
+    pub fn with_slack(mut self, slack: usize) -> Self {
+        self.slack = slack;
+        self
+    }
+
+    pub fn knots(&self) -> &[Position] {
+        &self.knots
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn visited(&self, knot: usize) -> &HashSet<Position> {
+        &self.visited[knot]
+    }
+
+    // the step on which `knot` first moved back onto a cell it had already been on
+    pub fn first_revisit(&self, knot: usize) -> Option<usize> {
+        self.first_revisit[knot]
+    }
+
+    // moves the head a single step; anything longer than one cell is clamped per axis
+    pub fn step_head(&mut self, dx: isize, dy: isize) {
+        let delta = (dx.signum(), dy.signum());
+        if delta == (0, 0) {
+            return;
+        }
+
+        self.steps += 1;
+        self.knots[0].step(delta);
+        self.record(0);
+
+        for knot in 1..self.knots.len() {
+            let (ahead, behind) = self.knots.split_at_mut(knot);
+            if behind[0].chase(&ahead[knot - 1], self.rule, self.slack) {
+                self.record(knot);
+            }
+        }
+    }
+
+    // moves the head along an arbitrary vector, one king-step at a time
+    pub fn move_head(&mut self, dx: isize, dy: isize) {
+        let (mut rx, mut ry) = (dx, dy);
+
+        while rx != 0 || ry != 0 {
+            self.step_head(rx, ry);
+            rx -= rx.signum();
+            ry -= ry.signum();
+        }
+    }
+
+    fn record(&mut self, knot: usize) {
+        if !self.visited[knot].insert(self.knots[knot].clone()) && self.first_revisit[knot].is_none() {
+            self.first_revisit[knot] = Some(self.steps);
+        }
+    }
+}
+
+impl Render for Rope {
+    // the head is drawn as 'H', other knots by index and the origin as 's', with y pointing up
+    fn render(&self, canvas: &mut Canvas) {
+        let origin = Position::new();
+        let all = || self.knots.iter().chain(std::iter::once(&origin));
+
+        // unwrap: `all` always yields at least the origin
+        let min_x = all().map(|p| p.x).min().unwrap();
+        let max_x = all().map(|p| p.x).max().unwrap();
+        let min_y = all().map(|p| p.y).min().unwrap();
+        let max_y = all().map(|p| p.y).max().unwrap();
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                canvas.put((x - min_x) as usize, (max_y - y) as usize, '.');
+            }
+        }
+
+        canvas.put((-min_x) as usize, max_y as usize, 's');
+
+        for (idx, knot) in self.knots.iter().enumerate().rev() {
+            let glyph = if idx == 0 { 'H' } else { char::from_digit(idx as u32, 36).unwrap_or('#') };
+            canvas.put((knot.x - min_x) as usize, (max_y - knot.y) as usize, glyph);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(rope: &mut Rope, moves: &[(isize, isize)]) {
+        for &(dx, dy) in moves {
+            rope.move_head(dx, dy);
+        }
+    }
+
+    const SAMPLE: [(isize, isize); 8] = [(4, 0), (0, 4), (-3, 0), (0, -1), (4, 0), (0, -1), (-5, 0), (2, 0)];
+    const LARGER: [(isize, isize); 8] = [(5, 0), (0, 8), (-8, 0), (0, -3), (17, 0), (0, -10), (-25, 0), (0, 20)];
+
+    #[test]
+    fn sample_tail() {
+        let mut rope = Rope::new(2);
+        run(&mut rope, &SAMPLE);
+        assert_eq!(rope.visited(1).len(), 13);
+        assert_eq!(rope.steps(), 24);
+    }
+
+    #[test]
+    fn larger_sample_every_knot() {
+        let mut rope = Rope::new(10);
+        run(&mut rope, &LARGER);
+        assert_eq!(rope.visited(9).len(), 36);
+        assert!(rope.visited(0).len() >= rope.visited(1).len());
+    }
+
+    #[test]
+    fn diagonal_head_drags_tail_diagonally() {
+        let mut rope = Rope::new(2);
+        rope.move_head(3, 3);
+        assert_eq!(rope.knots()[0], Position { x: 3, y: 3 });
+        assert_eq!(rope.knots()[1], Position { x: 2, y: 2 });
+    }
+
+    #[test]
+    fn orthogonal_rule() {
+        let mut rope = Rope::new(2).with_rule(FollowRule::Orthogonal);
+        rope.move_head(1, 1);
+        assert_eq!(rope.knots()[1], Position { x: 1, y: 0 });
+        rope.move_head(0, 2);
+        assert_eq!(rope.knots()[1], Position { x: 1, y: 2 });
+    }
+
+    #[test]
+    fn slack() {
+        let mut rope = Rope::new(2).with_slack(2);
+        rope.move_head(3, 0);
+        assert_eq!(rope.knots()[1], Position::new());
+        rope.move_head(1, 0);
+        assert_eq!(rope.knots()[1], Position { x: 1, y: 0 });
+    }
+
+    #[test]
+    fn revisit() {
+        let mut rope = Rope::new(2);
+        rope.move_head(3, 0);
+        rope.move_head(-3, 0);
+        assert_eq!(rope.first_revisit(0), Some(4));
+        assert_eq!(rope.first_revisit(1), Some(6));
+        assert_eq!(rope.visited(1).len(), 3);
+    }
+
+    #[test]
+    fn render() {
+        let mut rope = Rope::new(3);
+        rope.move_head(3, 0);
+        rope.move_head(0, 2);
+        assert_eq!(rope.text(), "...H\n..21\ns...\n");
+    }
+}
@@ -0,0 +1,138 @@
+use num_traits::Zero;
+
+use crate::BigUint;
+
+// the operations matrix powers need from the numbers they count with
+pub trait Count: Clone + PartialEq {
+    fn from_u64(n: u64) -> Self;
+    fn plus(&self, other: &Self) -> Self;
+    fn times(&self, other: &Self) -> Self;
+    // panics when `other` is the larger count, except under a modulus, where it wraps
+    fn minus(&self, other: &Self) -> Self;
+
+    fn zero() -> Self {
+        Self::from_u64(0)
+    }
+
+    fn one() -> Self {
+        Self::from_u64(1)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+impl Count for u64 {
+    fn from_u64(n: u64) -> Self {
+        n
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        self.checked_add(*other).expect("u64 count overflowed; count with BigUint or Mod instead")
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        self.checked_mul(*other).expect("u64 count overflowed; count with BigUint or Mod instead")
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        self.checked_sub(*other).expect("u64 count went negative")
+    }
+}
+
+impl Count for u128 {
+    fn from_u64(n: u64) -> Self {
+        n as u128
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        self.checked_add(*other).expect("u128 count overflowed; count with BigUint or Mod instead")
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        self.checked_mul(*other).expect("u128 count overflowed; count with BigUint or Mod instead")
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        self.checked_sub(*other).expect("u128 count went negative")
+    }
+}
+
+impl Count for BigUint {
+    fn from_u64(n: u64) -> Self {
+        BigUint::from(n)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        self * other
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        self - other
+    }
+
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+}
+
+// a count taken modulo M. the modulus is part of the type, so it has to be known at compile
+// time; for a modulus only known at runtime, count with BigUint and reduce the result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Mod<const M: u64>(pub u64);
+
+impl<const M: u64> Count for Mod<M> {
+    fn from_u64(n: u64) -> Self {
+        Mod(n % M)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        Mod(((self.0 as u128 + other.0 as u128) % M as u128) as u64)
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        Mod(((self.0 as u128 * other.0 as u128) % M as u128) as u64)
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        Mod(((self.0 as u128 + M as u128 - (other.0 % M) as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> std::fmt::Display for Mod<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn modular() {
+        let a = Mod::<7>::from_u64(12);
+        assert_eq!(a, Mod(5));
+        assert_eq!(a.plus(&Mod(4)), Mod(2));
+        assert_eq!(a.times(&Mod(3)), Mod(1));
+        assert_eq!(Mod::<7>(2).minus(&a), Mod(4));
+        assert!(Mod::<7>::from_u64(14).is_zero());
+    }
+
+    #[test]
+    #[should_panic]
+    fn u64_overflow_panics() {
+        u64::MAX.plus(&1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn u64_underflow_panics() {
+        1u64.minus(&2);
+    }
+}
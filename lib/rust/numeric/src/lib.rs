@@ -0,0 +1,8 @@
+mod count;
+mod matrix;
+mod theory;
+
+pub use num_bigint::BigUint;
+pub use count::{Count, Mod};
+pub use matrix::Matrix;
+pub use theory::{crt, extended_gcd, gcd, lcm, mod_inverse, mod_pow, Congruence, CrtError};
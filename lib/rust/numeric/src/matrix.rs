@@ -0,0 +1,126 @@
+use crate::Count;
+
+// a square matrix, stored row-major
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    n: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Count> Matrix<T> {
+    pub fn zeros(n: usize) -> Self {
+        Matrix { n, cells: vec![T::zero(); n * n] }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zeros(n);
+        for i in 0..n {
+            m.set(i, i, T::one());
+        }
+        m
+    }
+
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        &self.cells[row * self.n + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) {
+        self.cells[row * self.n + col] = value;
+    }
+
+    pub fn mul(&self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.n, other.n, "matrix sizes differ");
+        let mut out = Self::zeros(self.n);
+
+        for i in 0..self.n {
+            for k in 0..self.n {
+                let a = self.get(i, k);
+                // transition matrices are mostly zeros, so skipping them pays off
+                if a.is_zero() {
+                    continue;
+                }
+                for j in 0..self.n {
+                    let b = other.get(k, j);
+                    if !b.is_zero() {
+                        let cell = &mut out.cells[i * self.n + j];
+                        *cell = cell.plus(&a.times(b));
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    // exponentiation by repeated squaring
+    pub fn pow(&self, mut exp: u64) -> Matrix<T> {
+        let mut result = Self::identity(self.n);
+        let mut base = self.clone();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+
+        result
+    }
+
+    // the column vector `self * v`
+    pub fn apply(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(self.n, v.len(), "vector length doesn't match matrix size");
+
+        (0..self.n).map(|i| {
+            (0..self.n).fold(T::zero(), |acc, k| acc.plus(&self.get(i, k).times(&v[k])))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BigUint, Mod};
+
+    fn fibonacci<T: Count>() -> Matrix<T> {
+        let mut m = Matrix::zeros(2);
+        m.set(0, 0, T::one());
+        m.set(0, 1, T::one());
+        m.set(1, 0, T::one());
+        m
+    }
+
+    #[test]
+    fn fibonacci_u64() {
+        let m = fibonacci::<u64>().pow(90);
+        assert_eq!(*m.get(0, 1), 2_880_067_194_370_816_120);
+        assert_eq!(fibonacci::<u64>().pow(0), Matrix::identity(2));
+    }
+
+    #[test]
+    fn fibonacci_big() {
+        let m = fibonacci::<BigUint>().pow(100);
+        assert_eq!(m.get(0, 1).to_string(), "354224848179261915075");
+    }
+
+    #[test]
+    fn fibonacci_mod() {
+        // the Pisano period for 10 is 60
+        let m = fibonacci::<Mod<10>>().pow(1_000_000_000_000_000_060);
+        let n = fibonacci::<Mod<10>>().pow(1_000_000_000_000_000_000);
+        assert_eq!(m.get(0, 1), n.get(0, 1));
+    }
+
+    #[test]
+    fn apply() {
+        let v = fibonacci::<u64>().pow(10).apply(&[1, 0]);
+        assert_eq!(v, vec![89, 55]);
+    }
+}
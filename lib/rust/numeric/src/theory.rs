@@ -0,0 +1,180 @@
+use std::fmt;
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b)).checked_mul(b).expect("lcm overflowed u64")
+}
+
+// (g, x, y) with a * x + b * y = g = gcd(a, b)
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+// the x in [0, m) with a * x = 1 mod m, if a and m are coprime
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a as i128, m as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m as i128) as u64)
+}
+
+pub fn mod_pow(base: u64, exp: u64, m: u64) -> u64 {
+    assert!(m > 0, "modulus must be positive");
+
+    let m = m as u128;
+    let (mut base, mut exp) = (base as u128 % m, exp);
+    let mut acc = 1 % m;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = acc * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+
+    acc as u64
+}
+
+// x = residue mod modulus, with the residue always reduced into [0, modulus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Congruence {
+    pub residue: u64,
+    pub modulus: u64,
+}
+
+impl Congruence {
+    pub fn new(residue: i128, modulus: u64) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        Congruence { residue: residue.rem_euclid(modulus as i128) as u64, modulus }
+    }
+
+    // the congruence satisfied by exactly the numbers satisfying both, when the moduli share
+    // factors the residues have to agree on them
+    pub fn combine(&self, other: &Congruence) -> Result<Congruence, CrtError> {
+        let (a1, m1) = (self.residue as i128, self.modulus as i128);
+        let (a2, m2) = (other.residue as i128, other.modulus as i128);
+
+        let (g, p, _) = extended_gcd(m1, m2);
+        let diff = a2 - a1;
+        if diff % g != 0 {
+            return Err(CrtError::Inconsistent);
+        }
+
+        let lcm = (m1 / g) as u128 * m2 as u128;
+        if lcm > u64::MAX as u128 {
+            return Err(CrtError::Overflow);
+        }
+
+        // m1 * p = g mod m2, so stepping a1 by m1 * p * diff / g lands on a2 mod m2; the factors
+        // are reduced first and multiplied unsigned so they can't overflow
+        let n = (m2 / g) as u128;
+        let step = ((diff / g).rem_euclid(n as i128) as u128 * p.rem_euclid(n as i128) as u128 % n) as i128;
+        Ok(Congruence::new(a1 + m1 * step, lcm as u64))
+    }
+}
+
+impl fmt::Display for Congruence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "x = {} mod {}", self.residue, self.modulus)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    // no number satisfies all the congruences
+    Inconsistent,
+    // the combined modulus doesn't fit in a u64
+    Overflow,
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrtError::Inconsistent => write!(f, "the congruences have no common solution"),
+            CrtError::Overflow => write!(f, "the combined modulus overflows u64"),
+        }
+    }
+}
+
+impl std::error::Error for CrtError {}
+
+// the chinese remainder theorem for any moduli, coprime or not
+pub fn crt(congruences: impl IntoIterator<Item = Congruence>) -> Result<Congruence, CrtError> {
+    congruences.into_iter().try_fold(Congruence::new(0, 1), |acc, c| acc.combine(&c))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+    }
+
+    #[test]
+    fn inverse_and_pow() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, 1_000_000_007), 254_368_884);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn coprime() {
+        let x = crt([Congruence::new(2, 3), Congruence::new(3, 5), Congruence::new(2, 7)]);
+        assert_eq!(x, Ok(Congruence { residue: 23, modulus: 105 }));
+    }
+
+    #[test]
+    fn shared_factors() {
+        let x = crt([Congruence::new(3, 4), Congruence::new(5, 6)]);
+        assert_eq!(x, Ok(Congruence { residue: 11, modulus: 12 }));
+
+        let x = crt([Congruence::new(1, 4), Congruence::new(2, 6)]);
+        assert_eq!(x, Err(CrtError::Inconsistent));
+    }
+
+    #[test]
+    fn negative_residues_and_overflow() {
+        assert_eq!(Congruence::new(-1, 5).residue, 4);
+        assert_eq!(crt([]), Ok(Congruence { residue: 0, modulus: 1 }));
+        let big = u64::MAX / 2;
+        assert_eq!(crt([Congruence::new(0, big), Congruence::new(0, big - 2)]), Err(CrtError::Overflow));
+    }
+}
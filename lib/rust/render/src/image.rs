@@ -0,0 +1,189 @@
+use crate::{Canvas, Render, Rgb};
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+// renders each cell as a `scale` x `scale` block of pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Image {
+    pub scale: usize,
+    pub foreground: Rgb,
+    pub background: Rgb,
+    // glyphs drawn as background whatever their color; spaces and the '.' most puzzles use for
+    // empty cells unless set otherwise
+    pub blank: &'static [char],
+}
+
+impl Image {
+    pub fn new(scale: usize) -> Self {
+        Image {
+            scale: scale.max(1),
+            foreground: Rgb(255, 255, 255),
+            background: Rgb(0, 0, 0),
+            blank: &[' ', '.'],
+        }
+    }
+
+    pub fn with_blank(mut self, blank: &'static [char]) -> Self {
+        self.blank = blank;
+        self
+    }
+
+    pub fn dimensions(&self, canvas: &Canvas) -> (usize, usize) {
+        (canvas.width() * self.scale, canvas.height() * self.scale)
+    }
+
+    // raw RGB triples, row-major
+    pub fn pixels<R: Render + ?Sized>(&self, r: &R) -> Vec<u8> {
+        let canvas = r.canvas();
+        let (width, height) = self.dimensions(&canvas);
+        let mut pixels = Vec::with_capacity(width * height * 3);
+
+        for py in 0..height {
+            for px in 0..width {
+                let cell = canvas.get(px / self.scale, py / self.scale);
+                let Rgb(r, g, b) = if self.blank.contains(&cell.glyph) {
+                    self.background
+                } else {
+                    cell.color.unwrap_or(self.foreground)
+                };
+                pixels.extend([r, g, b]);
+            }
+        }
+
+        pixels
+    }
+
+    pub fn ppm<R: Render + ?Sized>(&self, r: &R) -> Vec<u8> {
+        let (width, height) = self.dimensions(&r.canvas());
+
+        let mut out = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        out.extend(self.pixels(r));
+        out
+    }
+
+    // the image data is stored uncompressed, which keeps this dependency-free
+    pub fn png<R: Render + ?Sized>(&self, r: &R) -> Vec<u8> {
+        let (width, height) = self.dimensions(&r.canvas());
+        let pixels = self.pixels(r);
+
+        let mut raw = Vec::with_capacity(pixels.len() + height);
+        for row in pixels.chunks(width * 3) {
+            raw.push(0);
+            raw.extend(row);
+        }
+
+        let mut header = vec![];
+        header.extend((width as u32).to_be_bytes());
+        header.extend((height as u32).to_be_bytes());
+        // 8 bits per channel, truecolor, default compression/filter, no interlace
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut out = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut out, b"IHDR", &header);
+        write_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        write_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+
+    out.extend(crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn checker() -> Canvas {
+        let mut canvas = Canvas::new();
+        canvas.write(0, 0, "#.");
+        canvas.write(0, 1, ".#");
+        canvas
+    }
+
+    #[test]
+    fn ppm_scales_cells() {
+        let ppm = Image::new(2).ppm(&checker());
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), 4 * 4 * 3);
+        assert_eq!(&pixels[0..6], &[255; 6]);
+        assert_eq!(&pixels[6..12], &[0; 6]);
+    }
+
+    #[test]
+    fn configurable_blanks() {
+        let pixels = Image::new(1).with_blank(&[' ']).pixels(&checker());
+        assert_eq!(pixels, [255; 12]);
+    }
+
+    #[test]
+    fn png_structure() {
+        let png = Image::new(1).png(&checker());
+        assert_eq!(&png[..8], &PNG_SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..20], &2u32.to_be_bytes());
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+}
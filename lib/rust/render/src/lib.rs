@@ -0,0 +1,200 @@
+mod image;
+
+pub use image::Image;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Option<Rgb>,
+}
+
+impl Cell {
+    pub fn new(glyph: char) -> Self {
+        Cell { glyph, color: None }
+    }
+
+    pub fn colored(glyph: char, color: Rgb) -> Self {
+        Cell { glyph, color: Some(color) }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell::new(' ')
+    }
+}
+
+impl From<char> for Cell {
+    fn from(glyph: char) -> Self {
+        Cell::new(glyph)
+    }
+}
+
+// a grid of cells that grows to fit whatever is drawn on it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Canvas {
+    rows: Vec<Vec<Cell>>,
+    width: usize,
+}
+
+impl Canvas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Cell {
+        self.rows.get(y).and_then(|row| row.get(x)).copied().unwrap_or_default()
+    }
+
+    pub fn put(&mut self, x: usize, y: usize, cell: impl Into<Cell>) {
+        if self.rows.len() <= y {
+            self.rows.resize(y + 1, vec![]);
+        }
+
+        let row = &mut self.rows[y];
+        if row.len() <= x {
+            row.resize(x + 1, Cell::default());
+        }
+
+        row[x] = cell.into();
+        self.width = self.width.max(x + 1);
+    }
+
+    // writes `s` left to right starting at (x, y), returning the column after the last glyph
+    pub fn write(&mut self, x: usize, y: usize, s: &str) -> usize {
+        self.write_cells(x, y, s.chars().map(Cell::new))
+    }
+
+    pub fn write_colored(&mut self, x: usize, y: usize, s: &str, color: Rgb) -> usize {
+        self.write_cells(x, y, s.chars().map(|ch| Cell::colored(ch, color)))
+    }
+
+    fn write_cells(&mut self, x: usize, y: usize, cells: impl Iterator<Item = Cell>) -> usize {
+        let mut x = x;
+        for cell in cells {
+            self.put(x, y, cell);
+            x += 1;
+        }
+        x
+    }
+
+    // plain text with trailing whitespace stripped, suitable for golden files
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+
+        for row in self.rows.iter() {
+            let line: String = row.iter().map(|c| c.glyph).collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+
+        out
+    }
+
+    pub fn to_ascii(&self, color: bool) -> String {
+        let mut out = String::new();
+
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let cell = self.get(x, y);
+                match cell.color {
+                    Some(Rgb(r, g, b)) if color => {
+                        out.push_str(&format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, cell.glyph));
+                    }
+                    _ => out.push(cell.glyph),
+                }
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+pub trait Render {
+    fn render(&self, canvas: &mut Canvas);
+
+    fn canvas(&self) -> Canvas {
+        let mut canvas = Canvas::new();
+        self.render(&mut canvas);
+        canvas
+    }
+
+    fn text(&self) -> String {
+        self.canvas().to_text()
+    }
+
+    fn ascii(&self, color: bool) -> String {
+        self.canvas().to_ascii(color)
+    }
+}
+
+impl Render for Canvas {
+    fn render(&self, canvas: &mut Canvas) {
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                canvas.put(x, y, *cell);
+            }
+        }
+    }
+
+    fn canvas(&self) -> Canvas {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Diagonal(usize);
+
+    impl Render for Diagonal {
+        fn render(&self, canvas: &mut Canvas) {
+            for i in 0..self.0 {
+                canvas.put(i, i, '#');
+            }
+        }
+    }
+
+    #[test]
+    fn text_trims_trailing_blanks() {
+        assert_eq!(Diagonal(3).text(), "#\n #\n  #\n");
+    }
+
+    #[test]
+    fn ascii_pads_rows() {
+        assert_eq!(Diagonal(2).ascii(false), "# \n #\n");
+    }
+
+    #[test]
+    fn ascii_color() {
+        let mut canvas = Canvas::new();
+        canvas.write(0, 0, "a");
+        canvas.write_colored(1, 0, "b", Rgb(1, 2, 3));
+        assert_eq!(canvas.ascii(true), "a\x1b[38;2;1;2;3mb\x1b[0m\n");
+        assert_eq!(canvas.ascii(false), "ab\n");
+    }
+
+    #[test]
+    fn write_returns_next_column() {
+        let mut canvas = Canvas::new();
+        let x = canvas.write(2, 1, "abc");
+        assert_eq!(x, 5);
+        assert_eq!(canvas.width(), 5);
+        assert_eq!(canvas.height(), 2);
+        assert_eq!(canvas.get(3, 1), Cell::new('b'));
+        assert_eq!(canvas.get(9, 9), Cell::default());
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+
+// problems with the shape of the input rather than what's in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionError {
+    // the input had a different number of sections to the names given for them
+    Count { expected: usize, found: usize },
+    Missing(String),
+    // a section whose first line isn't a "name:" header, by its first line
+    NoHeader(usize),
+}
+
+impl fmt::Display for SectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SectionError::Count { expected, found } => write!(f, "expected {} sections, found {}", expected, found),
+            SectionError::Missing(name) => write!(f, "no section named {:?}", name),
+            SectionError::NoHeader(line) => write!(f, "line {}: section has no header", line),
+        }
+    }
+}
+
+impl std::error::Error for SectionError {}
+
+// an error from the input's shape or from one of the section parsers, the latter tagged with
+// where in the original file it happened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError<E> {
+    Section(SectionError),
+    Parse { line: usize, section: String, error: E },
+}
+
+impl<E> From<SectionError> for InputError<E> {
+    fn from(error: SectionError) -> Self {
+        InputError::Section(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for InputError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Section(e) => write!(f, "{}", e),
+            InputError::Parse { line, section, error } => write!(f, "line {} ({}): {}", line, section, error),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for InputError<E> {}
+
+// a run of non-blank lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    name: String,
+    // the 1-based file line of lines[0]
+    first: usize,
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Section<'a> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // the 1-based line in the original file the section starts on
+    pub fn first_line(&self) -> usize {
+        self.first
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn lines(&self) -> &[&'a str] {
+        &self.lines
+    }
+
+    // the lines paired with their line numbers in the original file
+    pub fn numbered(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        self.lines.iter().enumerate().map(move |(i, &l)| (self.first + i, l))
+    }
+
+    // the section's lines joined back together, without any carriage returns
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    // tags an error from the section's `offset`th line with its place in the file
+    pub fn error_at<E>(&self, offset: usize, error: E) -> InputError<E> {
+        InputError::Parse { line: self.first + offset, section: self.name.clone(), error }
+    }
+
+    // parses every line on its own
+    pub fn parse_lines<T, E>(&self, mut parse: impl FnMut(&'a str) -> Result<T, E>) -> Result<Vec<T>, InputError<E>> {
+        self.lines.iter().enumerate().map(|(i, &l)| parse(l).map_err(|e| self.error_at(i, e))).collect()
+    }
+
+    // parses the section as a whole; the parser says which of its lines an error is on
+    pub fn parse_with<T, E>(&self, parse: impl FnOnce(&[&'a str]) -> Result<T, (usize, E)>) -> Result<T, InputError<E>> {
+        parse(&self.lines).map_err(|(offset, e)| self.error_at(offset, e))
+    }
+
+    // parses the section's text in one go, blaming its first line for any error
+    pub fn parse<T: std::str::FromStr>(&self) -> Result<T, InputError<T::Err>> {
+        self.text().parse().map_err(|e| self.error_at(0, e))
+    }
+}
+
+// input split into sections at blank lines; blank here includes whitespace-only lines and a
+// lone '\r', and runs of blank lines at the start, end or between sections are all skipped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sections<'a> {
+    sections: Vec<Section<'a>>,
+}
+
+impl<'a> Sections<'a> {
+    // sections are named by their position until named otherwise
+    pub fn new(input: &'a str) -> Self {
+        let mut sections: Vec<Section<'a>> = vec![];
+        let mut current: Option<Section<'a>> = None;
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.trim().is_empty() {
+                sections.extend(current.take());
+                continue;
+            }
+
+            let section = current.get_or_insert_with(|| Section { name: String::new(), first: i + 1, lines: vec![] });
+            section.lines.push(line);
+        }
+        sections.extend(current);
+
+        for (i, section) in sections.iter_mut().enumerate() {
+            section.name = format!("section {}", i + 1);
+        }
+        Sections { sections }
+    }
+
+    // names the sections in order, failing unless there's exactly one section per name
+    pub fn named(mut self, names: &[&str]) -> Result<Self, SectionError> {
+        if names.len() != self.sections.len() {
+            return Err(SectionError::Count { expected: names.len(), found: self.sections.len() });
+        }
+        for (section, name) in self.sections.iter_mut().zip(names) {
+            section.name = name.to_string();
+        }
+        Ok(self)
+    }
+
+    // names each section by its first line, which has to be a "name:" header and is dropped
+    pub fn headed(mut self) -> Result<Self, SectionError> {
+        for section in self.sections.iter_mut() {
+            let name = section.lines[0].trim().strip_suffix(':').ok_or(SectionError::NoHeader(section.first))?;
+            section.name = name.to_string();
+            section.lines.remove(0);
+            section.first += 1;
+        }
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Section<'a>, SectionError> {
+        self.sections.iter().find(|s| s.name == name).ok_or_else(|| SectionError::Missing(name.to_string()))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Section<'a>> {
+        self.sections.iter()
+    }
+}
+
+impl<'s, 'a> IntoIterator for &'s Sections<'a> {
+    type Item = &'s Section<'a>;
+    type IntoIter = std::slice::Iter<'s, Section<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.sections.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn splits_on_blank_lines() {
+        let sections = Sections::new("\n\na\nb\n\n\n  \nc\n\n");
+        let found: Vec<(usize, Vec<&str>)> = sections.iter().map(|s| (s.first_line(), s.lines().to_vec())).collect();
+        assert_eq!(found, [(3, vec!["a", "b"]), (8, vec!["c"])]);
+        assert_eq!(sections.iter().map(|s| s.name()).collect::<Vec<_>>(), ["section 1", "section 2"]);
+        assert!(Sections::new("\n \n").is_empty());
+    }
+
+    #[test]
+    fn crlf() {
+        let sections = Sections::new("a\r\nb\r\n\r\nc\r\n");
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections.iter().next().unwrap().text(), "a\nb");
+    }
+
+    #[test]
+    fn naming() {
+        let sections = Sections::new("1,2\n\nfold x=1\n").named(&["points", "folds"]).unwrap();
+        assert_eq!(sections.get("folds").unwrap().lines(), ["fold x=1"]);
+        assert_eq!(sections.get("stacks"), Err(SectionError::Missing("stacks".to_string())));
+        assert_eq!(Sections::new("a\n").named(&["x", "y"]), Err(SectionError::Count { expected: 2, found: 1 }));
+
+        let sections = Sections::new("Monkey 0:\n  a\n\nMonkey 1:\n  b\n").headed().unwrap();
+        let second = sections.get("Monkey 1").unwrap();
+        assert_eq!((second.first_line(), second.lines()), (5, &["  b"][..]));
+        assert_eq!(Sections::new("a:\n\nb\n").headed(), Err(SectionError::NoHeader(3)));
+    }
+
+    #[test]
+    fn errors_keep_file_lines() {
+        let sections = Sections::new("1\n2\n\n3\nx\n4\n").named(&["first", "second"]).unwrap();
+        let second = sections.get("second").unwrap();
+
+        let err = second.parse_lines(|l| l.parse::<u32>()).unwrap_err();
+        assert!(matches!(&err, InputError::Parse { line: 5, section, .. } if section == "second"));
+        assert_eq!(err.to_string(), "line 5 (second): invalid digit found in string");
+
+        let err = second.parse_with(|lines| if lines.len() > 2 { Err((2, "too long")) } else { Ok(()) });
+        assert_eq!(err, Err(InputError::Parse { line: 6, section: "second".to_string(), error: "too long" }));
+
+        assert_eq!(sections.get("first").unwrap().parse_lines(|l| l.parse::<u32>()), Ok(vec![1, 2]));
+        assert!(matches!(second.parse::<u32>(), Err(InputError::Parse { line: 4, .. })));
+    }
+}